@@ -6,19 +6,29 @@ std.List
 std.List String
 
 
+Map String Json
+
+
+-- a single tuple as generic argument
 Map (String, Json)
 
 
-List (Map (String, Json)
+List (Map String Json)
+
+
+[Float]
+
+
+[[Float]]
 
 
-[]Float
+List (List String)
 
 
-[][]Float
+Map String Int?
 
 
-List List String
+(Int, String)
 
 
 List Uint8 -> T
@@ -0,0 +1,148 @@
+use nom::{
+    branch::alt,
+    combinator::map,
+    multi::many0,
+    sequence::{delimited, preceded, terminated},
+};
+
+use crate::{
+    ast::{
+        expr::{Expression, FullExpression, Literal, Value},
+        identifier::{is_keyword, Identifier, IdentifierPath},
+        keywords,
+    },
+    comment::parse_comment,
+    parse::*,
+    util::*,
+};
+
+/// Metadata attached to an item.
+/// e.g.
+/// @inline
+/// @deprecated "use foo"
+/// @test.ignore
+/// @cfg(debug)
+/// @since 1 2
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attribute<'a> {
+    pub span: &'a str,
+    pub path: IdentifierPath<'a>,
+    pub args: Vec<FullExpression<'a>>,
+}
+
+impl<'a> Parse<'a> for Attribute<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, path) = preceded(keywords::At::parse, attribute_path)(input)?;
+
+        let (rest, args) = alt((
+            // @cfg(debug, test)
+            delimited(
+                keywords::ParenOpen::parse,
+                joined_by0(FullExpression::parse_ws, keywords::Comma::parse_ws),
+                keywords::ParenClose::parse_ws,
+            ),
+            // @deprecated "use foo"
+            // Only literals may follow without parenthesis,
+            // as anything else may be confused with the annotated item.
+            many0(map(Literal::parse_ws, |l| {
+                FullExpression::Expression(Box::new(Expression::Value(Value::Literal(l))))
+            })),
+        ))(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, Attribute { span, path, args }))
+    }
+}
+
+/// Like an IdentifierPath, but keywords are allowed as well, e.g. `test.ignore`
+fn attribute_path(input: &str) -> Res<'_, IdentifierPath<'_>> {
+    use nom::{bytes::complete::take_while, combinator::verify, multi::separated_list1};
+
+    let keyword = map(
+        verify(
+            take_while(|c: char| c.is_ascii_lowercase() || c == '_'),
+            is_keyword,
+        ),
+        |value| Identifier { span: value, value },
+    );
+    let (rest, value) =
+        separated_list1(keywords::Dot::parse, alt((Identifier::parse, keyword)))(input)?;
+    let span = unsafe { from_to(input, rest) };
+
+    Ok((rest, IdentifierPath { span, value }))
+}
+
+/// Parses all attributes in front of an item,
+/// including the whitespace and comments following them.
+pub(crate) fn attributes(input: &str) -> Res<'_, Vec<Attribute<'_>>> {
+    many0(terminated(Attribute::parse, parse_comment))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_arguments() {
+        let (rest, got) = Attribute::parse("@inline").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(got.path, IdentifierPath::from_str("inline"));
+        assert!(got.args.is_empty());
+
+        let (rest, got) = Attribute::parse("@test.ignore").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(got.path.span, "test.ignore");
+        assert_eq!(got.path.value.len(), 2);
+
+        let (rest, got) = Attribute::parse("@deprecated \"use foo\"").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(got.args, vec![FullExpression::from_str("\"use foo\"")]);
+
+        let (rest, got) = Attribute::parse("@cfg(debug, target 'linux')").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            got.args,
+            vec![
+                FullExpression::from_str("debug"),
+                FullExpression::from_str("target 'linux'")
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_attributes() {
+        let input = "@inline
+            # only in debug builds
+            @cfg(debug)
+            fun";
+        let (rest, got) = attributes(input).unwrap();
+        assert_eq!(rest, "fun");
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[1].span, "@cfg(debug)");
+    }
+
+    #[test]
+    fn attributes_after_calls() {
+        // the attribute belongs to `h`, not to the call `g`
+        let ast = crate::Ast::from_source_code("fun f() = g\n@inline\nfun h() = 1").unwrap();
+        assert_eq!(ast.items.len(), 2);
+        let crate::ast::body::BodyItem::Function(h) = &ast.items[1] else {
+            panic!("expected a function, got {:?}", ast.items[1]);
+        };
+        assert_eq!(h.attributes.len(), 1);
+        assert_eq!(h.attributes[0].span, "@inline");
+
+        let ast =
+            crate::Ast::from_source_code("let x = y\n@deprecated \"no\"\ntype P = Int").unwrap();
+        assert_eq!(ast.items.len(), 2);
+        let crate::ast::body::BodyItem::TypeDecl(p) = &ast.items[1] else {
+            panic!("expected a type, got {:?}", ast.items[1]);
+        };
+        assert_eq!(p.attributes.len(), 1);
+        assert_eq!(
+            p.attributes[0].args,
+            vec![FullExpression::from_str("\"no\"")]
+        );
+    }
+}
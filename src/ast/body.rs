@@ -1,11 +1,11 @@
-use crate::ast::expr::{BlockExpression, StringLiteral};
+use crate::ast::expr::{when::Guard, BlockExpression, StringLiteral};
 
 use crate::ast::*;
 use crate::parse::*;
 use crate::util::*;
 
 use nom::combinator::{cut, opt};
-use nom::sequence::{delimited, pair};
+use nom::sequence::{pair, terminated};
 use nom::{branch::alt, combinator::map};
 
 use super::expr::FullExpression;
@@ -16,6 +16,8 @@ pub enum BodyItem<'a> {
     Function(function::Function<'a>),
     TypeDecl(TypeDecl<'a>),
     BuildinTypeDecl(BuildinTypeDecl<'a>),
+    Interface(Interface<'a>),
+    Impl(Impl<'a>),
     Test(Test<'a>),
     Let(Let<'a>),
 }
@@ -27,6 +29,8 @@ impl<'a> BodyItem<'a> {
             Function(f) => f.span,
             TypeDecl(t) => t.span,
             BuildinTypeDecl(t) => t.span,
+            Interface(i) => i.span,
+            Impl(i) => i.span,
             Test(t) => t.span,
             Let(l) => l.span,
         }
@@ -39,6 +43,8 @@ impl<'a> Parse<'a> for BodyItem<'a> {
             map(Test::parse, BodyItem::Test),
             map(TypeDecl::parse, BodyItem::TypeDecl),
             map(BuildinTypeDecl::parse, BodyItem::BuildinTypeDecl),
+            map(Interface::parse, BodyItem::Interface),
+            map(Impl::parse, BodyItem::Impl),
             map(function::Function::parse, BodyItem::Function),
             map(Let::parse, BodyItem::Let),
         ))(input)
@@ -51,13 +57,15 @@ impl<'a> Parse<'a> for BodyItem<'a> {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Test<'a> {
     pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
     pub name: StringLiteral<'a>,
     pub instructions: BlockExpression<'a>,
 }
 
 impl<'a> Parse<'a> for Test<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, _) = keywords::Test::parse(input)?;
+        let (rest, attributes) = attributes(input)?;
+        let (rest, _) = keywords::Test::parse(rest)?;
 
         let (rest, name) = cut(expr::StringLiteral::parse_ws)(rest)?;
         let (rest, instructions) = cut(expr::BlockExpression::parse_ws)(rest)?;
@@ -68,6 +76,7 @@ impl<'a> Parse<'a> for Test<'a> {
             rest,
             Test {
                 span,
+                attributes,
                 name,
                 instructions,
             },
@@ -75,23 +84,34 @@ impl<'a> Parse<'a> for Test<'a> {
     }
 }
 
+/// let x = 7
+/// let mut counter = 0
+/// let (a, b) = pair
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Let<'a> {
     pub span: &'a str,
-    pub identifier: Identifier<'a>,
+    pub attributes: Vec<Attribute<'a>>,
+    pub visibility: Visibility,
+    pub mutable: bool,
+    pub pattern: Guard<'a>,
     pub expr: FullExpression<'a>,
 }
 
 impl<'a> Parse<'a> for Let<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, (identifier, expr)) = pair(
-            delimited(
-                keywords::Let::parse,
-                Identifier::parse_ws,
-                keywords::Assign::parse_ws,
-            ),
+        let (rest, attributes) = attributes(input)?;
+        // export
+        let (rest, visibility) = Visibility::parse(rest)?;
+        // let
+        let (rest, _) = keywords::Let::parse(rest)?;
+        // mut
+        let (rest, mutable) = opt(keywords::Mut::parse_ws)(rest)?;
+        let mutable = mutable.is_some();
+
+        let (rest, (pattern, expr)) = pair(
+            terminated(Guard::parse_irrefutable_ws, keywords::Assign::parse_ws),
             FullExpression::parse_ws,
-        )(input)?;
+        )(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
@@ -99,7 +119,10 @@ impl<'a> Parse<'a> for Let<'a> {
             rest,
             Let {
                 span,
-                identifier,
+                attributes,
+                visibility,
+                mutable,
+                pattern,
                 expr,
             },
         ))
@@ -109,13 +132,17 @@ impl<'a> Parse<'a> for Let<'a> {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BuildinTypeDecl<'a> {
     pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
+    pub visibility: Visibility,
     pub name: Identifier<'a>,
     pub generic_symbols: Option<GenericSymbols<'a>>,
 }
 
 impl<'a> Parse<'a> for BuildinTypeDecl<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, _) = keywords::BuildinType::parse(input)?;
+        let (rest, attributes) = attributes(input)?;
+        let (rest, visibility) = Visibility::parse(rest)?;
+        let (rest, _) = keywords::BuildinType::parse(rest)?;
         let (rest, name) = Identifier::parse_ws(rest)?;
         let (rest, generic_symbols) = opt(GenericSymbols::parse_ws)(rest)?;
 
@@ -125,6 +152,8 @@ impl<'a> Parse<'a> for BuildinTypeDecl<'a> {
             rest,
             BuildinTypeDecl {
                 span,
+                attributes,
+                visibility,
                 name,
                 generic_symbols,
             },
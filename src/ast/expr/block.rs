@@ -1,8 +1,9 @@
 use nom::{
     branch::alt,
-    combinator::{map, opt},
+    character::complete::space0,
+    combinator::{cut, map, not, opt, verify},
     multi::many0,
-    sequence::preceded,
+    sequence::{pair, preceded, terminated},
 };
 
 use crate::{
@@ -10,8 +11,8 @@ use crate::{
     parse::*,
     util::*,
 };
-use expr::FullExpression;
-use identifier::Identifier;
+use expr::{when::Guard, FullExpression};
+use identifier::{Identifier, IdentifierPath};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BlockExpression<'a> {
@@ -32,15 +33,36 @@ impl<'a> Parse<'a> for BlockExpression<'a> {
     }
 }
 
+impl<'a> BlockExpression<'a> {
+    /// Parses the remaining parts of a block after the first one.
+    /// `input` starts at the opening curly brace.
+    pub(crate) fn parse_rest(
+        input: &'a str,
+        rest: &'a str,
+        first: BlockExpressionPart<'a>,
+    ) -> Res<'a, Self> {
+        let (rest, others) = many0(BlockExpressionPart::parse_ws)(rest)?;
+        let (rest, _) = keywords::CurlyClose::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+        let parts = std::iter::once(first).chain(others).collect();
+
+        Ok((rest, BlockExpression { span, parts }))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum BlockExpressionPart<'a> {
     Let(Let<'a>),
     Return(Return<'a>),
-    Break(&'a str),
-    Next(&'a str),
+    Yield(Yield<'a>),
+    Break(Break<'a>),
+    Next(Next<'a>),
     Loop(Loop<'a>),
+    While(While<'a>),
     If(If<'a>),
     For(For<'a>),
+    Assignment(Assignment<'a>),
     FullExpression(FullExpression<'a>),
     // ;
     Separator(&'a str),
@@ -48,36 +70,40 @@ pub enum BlockExpressionPart<'a> {
 
 impl<'a> Parse<'a> for BlockExpressionPart<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let brk = map(keywords::Break::parse, |keywords::Break { span }| {
-            BlockExpressionPart::Break(span)
-        });
-        let next = map(keywords::Next::parse, |keywords::Next { span }| {
-            BlockExpressionPart::Next(span)
-        });
         let sep = map(
             keywords::SemiColon::parse,
             |keywords::SemiColon { span }| BlockExpressionPart::Separator(span),
         );
 
         alt((
-            map(Let::parse, BlockExpressionPart::Let),
+            // only top level lets may be exported
+            map(
+                verify(Let::parse, |l| l.visibility == Visibility::Private),
+                BlockExpressionPart::Let,
+            ),
             map(Return::parse, BlockExpressionPart::Return),
-            brk,
-            next,
+            map(Yield::parse, BlockExpressionPart::Yield),
+            map(Break::parse, BlockExpressionPart::Break),
+            map(Next::parse, BlockExpressionPart::Next),
             map(Loop::parse, BlockExpressionPart::Loop),
+            map(While::parse, BlockExpressionPart::While),
             map(If::parse, BlockExpressionPart::If),
             map(For::parse, BlockExpressionPart::For),
+            map(Assignment::parse, BlockExpressionPart::Assignment),
             map(FullExpression::parse, BlockExpressionPart::FullExpression),
             sep,
         ))(input)
     }
 }
 
+/// if x > 0 do { print x }
+/// if x > 0 do { 1 } else if x < 0 do { 2 } else { 0 }
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct If<'a> {
     pub span: &'a str,
     pub condition: FullExpression<'a>,
     pub then: BlockExpression<'a>,
+    pub else_clause: Option<Box<ElseClause<'a>>>,
 }
 
 impl<'a> Parse<'a> for If<'a> {
@@ -85,11 +111,13 @@ impl<'a> Parse<'a> for If<'a> {
         // if
         let (rest, _) = keywords::If::parse(input)?;
         // <expr>
-        let (rest, condition) = FullExpression::parse_ws(rest)?;
+        let (rest, condition) = cut(FullExpression::parse_ws)(rest)?;
         // do
-        let (rest, _) = keywords::Do::parse_ws(rest)?;
+        let (rest, _) = cut(keywords::Do::parse_ws)(rest)?;
         // {<expr> ...}
-        let (rest, then) = BlockExpression::parse_ws(rest)?;
+        let (rest, then) = cut(BlockExpression::parse_ws)(rest)?;
+        // else ...
+        let (rest, else_clause) = opt(map(ElseClause::parse_ws, Box::new))(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
@@ -99,25 +127,97 @@ impl<'a> Parse<'a> for If<'a> {
                 span,
                 condition,
                 then,
+                else_clause,
             },
         ))
     }
 }
 
+impl<'a> If<'a> {
+    /// Parses an if statement, that is used as an expression.
+    /// In order to always produce a value, it must have an else clause.
+    ///
+    /// Nothing else starts with `if`, so a missing else clause is a failure.
+    /// Backtracking callers would otherwise parse the same `if` over and over.
+    pub fn parse_expression(input: &'a str) -> Res<'a, Self> {
+        let (rest, if_expression) = If::parse(input)?;
+
+        if if_expression.else_clause.is_none() {
+            return Err(nom::Err::Failure(nom::error::Error::new(
+                rest,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+
+        Ok((rest, if_expression))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ElseClause<'a> {
+    // else if x do { ... }
+    If(If<'a>),
+    // else { ... }
+    Block(BlockExpression<'a>),
+}
+
+impl<'a> ElseClause<'a> {
+    pub fn span(&self) -> &'a str {
+        match self {
+            ElseClause::If(i) => i.span,
+            ElseClause::Block(b) => b.span,
+        }
+    }
+}
+
+impl<'a> Parse<'a> for ElseClause<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        // else
+        let (rest, _) = keywords::Else::parse(input)?;
+
+        cut(alt((
+            map(If::parse_ws, ElseClause::If),
+            map(BlockExpression::parse_ws, ElseClause::Block),
+        )))(rest)
+    }
+}
+
+/// Loops may be labeled, so that `break` and `next`
+/// can refer to an outer loop.
+///
+/// 'outer: loop { ... }
+fn label(input: &str) -> Res<'_, Option<Identifier<'_>>> {
+    opt(terminated(label_name, keywords::Colon::parse_ws))(input)
+}
+
+/// The name of a label, e.g. `'outer`
+///
+/// `'outer'` is a string, not a label.
+fn label_name(input: &str) -> Res<'_, Identifier<'_>> {
+    terminated(
+        preceded(keywords::Label::parse, Identifier::parse),
+        not(keywords::Label::parse),
+    )(input)
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct For<'a> {
     pub span: &'a str,
-    pub variable: Identifier<'a>,
+    pub label: Option<Identifier<'a>>,
+    pub pattern: Guard<'a>,
     pub over: FullExpression<'a>,
     pub body: BlockExpression<'a>,
 }
 
 impl<'a> Parse<'a> for For<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
+        // 'outer:
+        let (rest, label) = label(input)?;
         // for
-        let (rest, _) = keywords::For::parse(input)?;
+        let (rest, _) = keywords::For::parse_ws(rest)?;
         // x
-        let (rest, variable) = Identifier::parse_ws(rest)?;
+        // (key, value)
+        let (rest, pattern) = Guard::parse_irrefutable_ws(rest)?;
         // in
         let (rest, _) = keywords::In::parse_ws(rest)?;
         // list     e.g. <expr>
@@ -133,7 +233,8 @@ impl<'a> Parse<'a> for For<'a> {
             rest,
             For {
                 span,
-                variable,
+                label,
+                pattern,
                 over,
                 body,
             },
@@ -144,19 +245,182 @@ impl<'a> Parse<'a> for For<'a> {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Loop<'a> {
     pub span: &'a str,
+    pub label: Option<Identifier<'a>>,
     pub body: BlockExpression<'a>,
 }
 
 impl<'a> Parse<'a> for Loop<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
+        // 'outer:
+        let (rest, label) = label(input)?;
         // loop
-        let (rest, _) = keywords::Loop::parse(input)?;
+        let (rest, _) = keywords::Loop::parse_ws(rest)?;
+        // {<expr> ...}
+        let (rest, body) = BlockExpression::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, Loop { span, label, body }))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct While<'a> {
+    pub span: &'a str,
+    pub label: Option<Identifier<'a>>,
+    pub condition: FullExpression<'a>,
+    pub body: BlockExpression<'a>,
+}
+
+impl<'a> Parse<'a> for While<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        // 'outer:
+        let (rest, label) = label(input)?;
+        // while
+        let (rest, _) = keywords::While::parse_ws(rest)?;
+        // <expr>
+        let (rest, condition) = FullExpression::parse_ws(rest)?;
+        // do
+        let (rest, _) = keywords::Do::parse_ws(rest)?;
         // {<expr> ...}
         let (rest, body) = BlockExpression::parse_ws(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
-        Ok((rest, Loop { span, body }))
+        Ok((
+            rest,
+            While {
+                span,
+                label,
+                condition,
+                body,
+            },
+        ))
+    }
+}
+
+/// break
+/// break 'outer
+/// break 7
+/// break result
+/// break 'outer (x + 1)
+///
+/// The label and the value need to be on the same line as the `break`.
+/// Just like function arguments, the value needs to be wrapped in parenthesis,
+/// if it is anything other than a single value, e.g. `break (x + 1)`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Break<'a> {
+    pub span: &'a str,
+    pub label: Option<Identifier<'a>>,
+    pub value: Option<expr::Value<'a>>,
+}
+
+impl<'a> Parse<'a> for Break<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, _) = keywords::Break::parse(input)?;
+        // 'outer
+        // A single quoted string on the same line is a value though,
+        // e.g. `break 'not found'`
+        let single_line_string = verify(expr::IString::parse, |s| !s.span.contains('\n'));
+        let (rest, label) = opt(preceded(pair(space0, not(single_line_string)), label_name))(rest)?;
+        // <value>
+        let (rest, value) = opt(preceded(space0, expr::Value::parse))(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, Break { span, label, value }))
+    }
+}
+
+/// next
+/// next 'outer
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Next<'a> {
+    pub span: &'a str,
+    pub label: Option<Identifier<'a>>,
+}
+
+impl<'a> Parse<'a> for Next<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, _) = keywords::Next::parse(input)?;
+        let (rest, label) = opt(preceded(space0, label_name))(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, Next { span, label }))
+    }
+}
+
+/// x = 7
+/// point.x += 1
+/// list ++= [x]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Assignment<'a> {
+    pub span: &'a str,
+    pub target: IdentifierPath<'a>,
+    pub operator: AssignOperator,
+    pub value: FullExpression<'a>,
+}
+
+impl<'a> Parse<'a> for Assignment<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        // point.x
+        let (rest, target) = IdentifierPath::parse(input)?;
+        // +=
+        let (rest, operator) = AssignOperator::parse_ws(rest)?;
+        // <expr>
+        let (rest, value) = FullExpression::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((
+            rest,
+            Assignment {
+                span,
+                target,
+                operator,
+                value,
+            },
+        ))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AssignOperator {
+    // =
+    Assign,
+    // +=
+    Add,
+    // -=
+    Subtract,
+    // *=
+    Multiply,
+    // /=
+    Divide,
+    // ++=
+    Concat,
+}
+
+impl<'a> Parse<'a> for AssignOperator {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        use nom::character::complete::one_of;
+
+        alt((
+            map(keywords::ConcatAssign::parse, |_| AssignOperator::Concat),
+            map(keywords::AddAssign::parse, |_| AssignOperator::Add),
+            map(keywords::SubtractAssign::parse, |_| {
+                AssignOperator::Subtract
+            }),
+            map(keywords::MultiplyAssign::parse, |_| {
+                AssignOperator::Multiply
+            }),
+            map(keywords::DivideAssign::parse, |_| AssignOperator::Divide),
+            // = may not be mistaken for == or =>
+            map(
+                terminated(keywords::Assign::parse, not(one_of("=>"))),
+                |_| AssignOperator::Assign,
+            ),
+        ))(input)
     }
 }
 
@@ -177,6 +441,23 @@ impl<'a> Parse<'a> for Return<'a> {
     }
 }
 
+/// Produces the next value of a generator, e.g. `yield n`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Yield<'a> {
+    pub span: &'a str,
+    pub value: FullExpression<'a>,
+}
+
+impl<'a> Parse<'a> for Yield<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, value) = preceded(keywords::Yield::parse, cut(FullExpression::parse_ws))(input)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, Yield { span, value }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,6 +485,7 @@ mod tests {
                 span: "if true do {something}",
                 condition: FullExpression::parse("true").unwrap().1,
                 then: BlockExpression::parse("{something}").unwrap().1,
+                else_clause: None,
             }
         );
         assert_eq!(rest, " ");
@@ -214,7 +496,259 @@ mod tests {
         return_statement,
         ["return", "return 7", "return None"]
     );
-    derive_tests!(If, if_statement, ["if !true do { print x }"]);
-    derive_tests!(Loop, loop_statement, ["loop {}"]);
-    derive_tests!(Let, let_statement, ["let x = tag n"]);
+
+    derive_tests!(Yield, yield_statement, ["yield 7", "yield n * 2"]);
+    #[test]
+    fn if_else_chain() {
+        let input = "if a do { 1 } else if b do { 2 } else { 3 }";
+        let (rest, statement) = If::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let Some(else_clause) = statement.else_clause else {
+            panic!("expected else clause");
+        };
+        let ElseClause::If(else_if) = *else_clause else {
+            panic!("expected else if");
+        };
+        assert_eq!(else_if.span, "if b do { 2 } else { 3 }");
+        assert!(matches!(
+            else_if.else_clause.as_deref(),
+            Some(ElseClause::Block(_))
+        ));
+    }
+
+    #[test]
+    fn if_expression_needs_else() {
+        assert!(If::parse_expression("if a do { 1 }").is_err());
+        assert!(If::parse_expression("if a do { 1 } else { 2 }").is_ok());
+    }
+
+    #[test]
+    fn deeply_nested_if_expressions() {
+        // every level used to parse the whole `if` once per alternative of its caller
+        let depth = 16;
+        let input = format!("{}1{}", "f (if a do { ".repeat(depth), " })".repeat(depth));
+        assert!(matches!(
+            FullExpression::parse(&input),
+            Err(nom::Err::Failure(_))
+        ));
+
+        let input = format!(
+            "{}1{}",
+            "f (if a do { ".repeat(depth),
+            " } else { 2 })".repeat(depth)
+        );
+        let (rest, _) = FullExpression::parse(&input).unwrap();
+        assert_eq!(rest, "");
+    }
+
+    #[test]
+    fn if_else_if_with_comparisons() {
+        let input = "if x > 0 do { 1 } else if x < 0 do { 2 } else { 0 }";
+        let (rest, _) = If::parse(input).unwrap();
+        assert_eq!(rest, "");
+    }
+
+    derive_tests!(
+        If,
+        if_statement,
+        [
+            "if !true do { print x }",
+            "if x do { print x } else { print y }",
+            "if x do { print x }\n else if y do { print y }\n else { print z }"
+        ]
+    );
+    #[test]
+    fn labeled_loop() {
+        let input = "'outer: loop { loop { break 'outer 7 } }";
+        let (rest, statement) = Loop::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(statement.label, Some(Identifier::from_str("outer")));
+
+        let BlockExpressionPart::Loop(inner) = &statement.body.parts[0] else {
+            panic!("expected inner loop");
+        };
+        assert_eq!(inner.label, None);
+        assert_eq!(
+            inner.body.parts[0],
+            BlockExpressionPart::Break(Break {
+                span: "break 'outer 7",
+                label: Some(Identifier::from_str("outer")),
+                value: Some(expr::Value::from_str("7")),
+            })
+        );
+    }
+
+    #[test]
+    fn break_value() {
+        let (rest, statement) = Break::parse("break (x + 1)").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(statement.label, None);
+        assert!(statement.value.is_some());
+
+        let (rest, statement) = Break::parse("break 'outer").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(statement.label, Some(Identifier::from_str("outer")));
+        assert_eq!(statement.value, None);
+
+        let (rest, statement) = Break::parse("break result").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(statement.label, None);
+        assert_eq!(statement.value, Some(expr::Value::from_str("result")));
+
+        let (rest, statement) = Break::parse("break 'outer result").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(statement.label, Some(Identifier::from_str("outer")));
+        assert_eq!(statement.value, Some(expr::Value::from_str("result")));
+
+        let (rest, statement) = Break::parse("break 'not found'").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(statement.label, None);
+        assert_eq!(statement.value, Some(expr::Value::from_str("'not found'")));
+    }
+
+    #[test]
+    fn break_on_its_own_line() {
+        let (rest, statement) = Break::parse("break\nprint x").unwrap();
+        assert_eq!(rest, "\nprint x");
+        assert_eq!(statement.label, None);
+        assert_eq!(statement.value, None);
+
+        let (rest, statement) = Next::parse("next\n'done'").unwrap();
+        assert_eq!(rest, "\n'done'");
+        assert_eq!(statement.label, None);
+
+        let input = "{ loop { break\nresult } }";
+        let (rest, block) = BlockExpression::parse(input).unwrap();
+        assert_eq!(rest, "");
+        let BlockExpressionPart::Loop(inner) = &block.parts[0] else {
+            panic!("expected loop");
+        };
+        assert_eq!(inner.body.parts.len(), 2);
+    }
+
+    derive_tests!(Loop, loop_statement, ["loop {}", "'outer: loop {}"]);
+    derive_tests!(
+        While,
+        while_statement,
+        [
+            "while running do { step }",
+            "'outer:while x do { next 'outer }",
+            "'outer : while x do { break }"
+        ]
+    );
+    derive_tests!(Next, next_statement, ["next", "next 'outer"]);
+    derive_tests!(
+        For,
+        for_statement,
+        [
+            "for x in list do { print x }",
+            "for i in 0..n do { print i }",
+            "for i in 1..=len xs do { print i }",
+            "'rows: for row in rows do { for x in row do { next 'rows } }",
+            "for (k, v) in pairs do { print k }",
+            "for Point x px in points do { print px }"
+        ]
+    );
+    derive_tests!(
+        Let,
+        let_statement,
+        [
+            "let x = tag n",
+            "let mut x = 0",
+            "let (a, b) = pair",
+            "let [first, second, rest..] = xs",
+            "let Point x px y py = p",
+            "let mut (a, (b, c)) = nested"
+        ]
+    );
+
+    #[test]
+    fn refutable_patterns() {
+        for input in ["let 7 = x", "let 'a'..'z' = c", "let Some x | None = o"] {
+            assert!(Let::parse(input).is_err(), "{}", input);
+        }
+        assert!(For::parse("for 0 in xs do { print 0 }").is_err());
+        assert!(For::parse("for [a, b] in pairs do { print a }").is_err());
+    }
+
+    #[test]
+    fn let_binds_variable() {
+        let (_, statement) = Let::parse("let x = 7").unwrap();
+        assert_eq!(
+            statement.pattern,
+            Guard::VariableBinding(Identifier::from_str("x"))
+        );
+    }
+
+    #[test]
+    fn assignments() {
+        let input = "point.x += 1";
+        let (rest, statement) = Assignment::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            statement,
+            Assignment {
+                span: input,
+                target: IdentifierPath::from_str("point.x"),
+                operator: AssignOperator::Add,
+                value: FullExpression::from_str("1"),
+            }
+        );
+
+        let operators = [
+            ("x = 1", AssignOperator::Assign),
+            ("x -= 1", AssignOperator::Subtract),
+            ("x *= 2", AssignOperator::Multiply),
+            ("x /= 2", AssignOperator::Divide),
+            ("xs ++= [x]", AssignOperator::Concat),
+        ];
+
+        for (input, operator) in operators {
+            let (rest, statement) = Assignment::parse(input).unwrap();
+            assert_eq!(rest, "");
+            assert_eq!(statement.operator, operator);
+        }
+    }
+
+    #[test]
+    fn assignment_in_block() {
+        let input = "{ let mut n = 0; n += 1; print n = 2 }";
+        let (rest, block) = BlockExpression::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert!(matches!(
+            block.parts[0],
+            BlockExpressionPart::Let(Let { mutable: true, .. })
+        ));
+        assert!(matches!(block.parts[2], BlockExpressionPart::Assignment(_)));
+        // named function arguments are no assignment
+        assert!(matches!(
+            block.parts[4],
+            BlockExpressionPart::FullExpression(_)
+        ));
+    }
+
+    #[test]
+    fn statements_on_separate_lines() {
+        let input = "{
+            let mut n = 0
+            print x
+            n += 1
+            print x
+            x = 1
+            p = Point x=1 y=2
+            n = 2
+        }";
+        let (rest, block) = BlockExpression::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(block.parts.len(), 7);
+        assert!(matches!(block.parts[2], BlockExpressionPart::Assignment(_)));
+        assert!(matches!(block.parts[4], BlockExpressionPart::Assignment(_)));
+        assert!(matches!(block.parts[6], BlockExpressionPart::Assignment(_)));
+
+        let BlockExpressionPart::FullExpression(call) = &block.parts[3] else {
+            panic!("expected a call, got {:?}", block.parts[3]);
+        };
+        assert_eq!(call.span(), "print x");
+    }
 }
@@ -1,24 +1,33 @@
 use nom::{
     combinator::{cut, map, opt},
-    multi::separated_list0,
-    sequence::{delimited, pair, preceded},
+    sequence::{delimited, preceded},
 };
 
-use crate::ast::identifier::Identifier;
 use crate::{ast::*, parse::*, util::*};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Closure<'a> {
     pub span: &'a str,
+    pub is_async: bool,
     pub arguments: ClosureArgs<'a>,
     pub ret: Option<Type<'a>>,
+    /// Type of the values produced by `yield`
+    pub yields: Option<Type<'a>>,
     pub body: Box<expr::FullExpression<'a>>,
 }
 
 impl<'a> Parse<'a> for Closure<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
+        // async
+        let (rest, is_async) = opt(keywords::Async::parse)(input)?;
+        let is_async = is_async.is_some();
+
         // fun
-        let (rest, _) = keywords::Function::parse(input)?;
+        let (rest, _) = if is_async {
+            keywords::Function::parse_ws(rest)?
+        } else {
+            keywords::Function::parse(rest)?
+        };
 
         // (x)
         let (rest, arguments) = ClosureArgs::parse_ws(rest)?;
@@ -26,6 +35,9 @@ impl<'a> Parse<'a> for Closure<'a> {
         // -> Int
         let (rest, ret) = opt(preceded(keywords::ThinArrow::parse, cut(Type::parse_ws)))(rest)?;
 
+        // yields Int
+        let (rest, yields) = opt(preceded(keywords::Yields::parse_ws, cut(Type::parse_ws)))(rest)?;
+
         // =>
         let (rest, _) = keywords::FatArrow::parse_ws(rest)?;
         // x^2
@@ -37,8 +49,10 @@ impl<'a> Parse<'a> for Closure<'a> {
             rest,
             Closure {
                 span,
+                is_async,
                 arguments,
                 ret,
+                yields,
                 body,
             },
         ))
@@ -48,7 +62,7 @@ impl<'a> Parse<'a> for Closure<'a> {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClosureArgs<'a> {
     pub span: &'a str,
-    pub args: Vec<(Identifier<'a>, Option<ty::Type<'a>>)>,
+    pub args: Vec<Param<'a>>,
 }
 
 impl<'a> Parse<'a> for ClosureArgs<'a> {
@@ -56,16 +70,7 @@ impl<'a> Parse<'a> for ClosureArgs<'a> {
         use keywords::*;
         let (rest, args) = delimited(
             ParenOpen::parse,
-            separated_list0(
-                Comma::parse_ws,
-                pair(
-                    Identifier::parse_ws,
-                    opt(preceded(
-                        keywords::TypeHint::parse_ws,
-                        cut(ty::Type::parse_ws),
-                    )),
-                ),
-            ),
+            crate::ast::function::params,
             cut(ParenClose::parse_ws),
         )(input)?;
 
@@ -94,7 +99,13 @@ mod tests {
     derive_tests!(
         Closure,
         closure,
-        ["fun(x)=> x+2", "fun (x, y)-> Int => x + y", "fun () => 7"]
+        [
+            "fun(x)=> x+2",
+            "fun (x, y)-> Int => x + y",
+            "fun () => 7",
+            "async fun (url) => await fetch url",
+            "fun (xs) yields Int => { for x in xs do { yield x * 2 } }"
+        ]
     );
 
     derive_tests!(
@@ -105,7 +116,20 @@ mod tests {
             "()",
             "(x, y)",
             "(x: Float, y: Float)",
-            "(x: Float, y: Float, info)"
+            "(x: Float, y: Float, info)",
+            "(x, step: Int = 1)",
+            "(f, rest..)"
         ]
     );
+
+    #[test]
+    fn misplaced_variadic_argument() {
+        for input in ["(rest.., f)", "(rest.. = 1)"] {
+            assert!(
+                matches!(ClosureArgs::parse(input), Err(nom::Err::Failure(_))),
+                "{}",
+                input
+            );
+        }
+    }
 }
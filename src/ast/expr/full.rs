@@ -15,6 +15,17 @@ pub enum FullExpression<'a> {
 
     Or(Or<'a>),
     And(And<'a>),
+
+    // a + 1 < b
+    // <=>
+    // (a + 1) < b
+    Comparison(Comparison<'a>),
+
+    // 0..n+1
+    // <=>
+    // 0..(n+1)
+    Range(Range<'a>),
+
     Concat(Concat<'a>),
 
     Add(Add<'a>),
@@ -26,6 +37,11 @@ pub enum FullExpression<'a> {
     // Rarely used, because mostly Value::Power takes precedence.
     Power(Power<'a>),
 
+    // await fetch url + 1
+    // <=>
+    // (await fetch url) + 1
+    Await(Await<'a>),
+
     // list /> filter ft : map n * 3 ++ [end_elem]
     // <=>
     // ( list /> filter ft : map n ) * 3 ++ [end_elem]
@@ -46,12 +62,15 @@ impl<'a> FullExpression<'a> {
             Self::Let(n) => n.span,
             Self::Or(s) => s.span,
             Self::And(s) => s.span,
+            Self::Comparison(s) => s.span,
+            Self::Range(s) => s.span,
             Self::Concat(s) => s.span,
             Self::Add(s) => s.span,
             Self::Subtract(s) => s.span,
             Self::Multiply(s) => s.span,
             Self::Divide(s) => s.span,
             Self::Power(s) => s.span,
+            Self::Await(s) => s.span,
             Self::Pipe(s) => s.span,
             Self::Expression(s) => s.span(),
         }
@@ -71,9 +90,9 @@ trait ParseExpression<'a>
 where
     Self: Sized,
 {
-    fn parse(input: &'a str) -> Res<'a, FullExpression>;
+    fn parse(input: &'a str) -> Res<'a, FullExpression<'a>>;
 
-    fn parse_ws(input: &'a str) -> Res<'a, FullExpression> {
+    fn parse_ws(input: &'a str) -> Res<'a, FullExpression<'a>> {
         ws(Self::parse)(input)
     }
 }
@@ -125,6 +144,7 @@ macro_rules! create_ast_expr {
                     FunctionArg {
                         span,
                         name: None,
+                        spread: false,
                         value: expr::Value::Tuple(expr::Tuple {
                             span,
                             values: vec![*self.left.clone()],
@@ -138,6 +158,7 @@ macro_rules! create_ast_expr {
                     FunctionArg {
                         span,
                         name: None,
+                        spread: false,
                         value: expr::Value::Tuple(expr::Tuple {
                             span,
                             values: vec![*self.right.clone()],
@@ -148,6 +169,7 @@ macro_rules! create_ast_expr {
                 let fc = expr::FunctionCall {
                     span,
                     function_name,
+                    type_args: Vec::new(),
                     args: vec![left, right],
                 };
 
@@ -158,13 +180,168 @@ macro_rules! create_ast_expr {
 }
 
 create_ast_expr!(Or, keywords::Or, And);
-create_ast_expr!(And, keywords::And, Concat);
+create_ast_expr!(And, keywords::And, Comparison);
 create_ast_expr!(Concat, keywords::Concat, Add);
 create_ast_expr!(Add, keywords::Add, Subtract);
 create_ast_expr!(Subtract, keywords::Subtract, Multiply);
 create_ast_expr!(Multiply, keywords::Multiply, Divide);
 create_ast_expr!(Divide, keywords::Divide, Power);
-create_ast_expr!(Power, keywords::Power, Pipe);
+create_ast_expr!(Power, keywords::Power, Await);
+
+/// Comparison of two values, e.g. `a == b` or `x + 1 >= y`
+///
+/// Comparisons do not chain, `a < b < c` is not a valid comparison.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Comparison<'a> {
+    pub span: &'a str,
+    pub left: Box<FullExpression<'a>>,
+    pub operator: ComparisonOperator,
+    pub right: Box<FullExpression<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Smaller,
+    SmallerEqual,
+}
+
+/// parses one of `==`, `!=`, `>=`, `<=`, `>` or `<`.
+fn comparison_operator(input: &str) -> Res<'_, ComparisonOperator> {
+    use ComparisonOperator::*;
+
+    alt((
+        map(keywords::Equal::parse, |_| Equal),
+        map(keywords::NotEqual::parse, |_| NotEqual),
+        map(keywords::GreaterEqual::parse, |_| GreaterEqual),
+        map(keywords::SmallerEqual::parse, |_| SmallerEqual),
+        map(keywords::Greater::parse, |_| Greater),
+        map(keywords::Smaller::parse, |_| Smaller),
+    ))(input)
+}
+
+impl<'a> ParseExpression<'a> for Comparison<'a> {
+    fn parse(input: &'a str) -> Res<'a, FullExpression<'a>> {
+        let (rest, left) = Range::parse(input)?;
+
+        if let Ok((rest, operator)) = ws(comparison_operator)(rest) {
+            let (rest, right) = Range::parse_ws(rest)?;
+            let span = unsafe { from_to(input, rest) };
+
+            return Ok((
+                rest,
+                FullExpression::Comparison(Comparison {
+                    span,
+                    left: Box::new(left),
+                    operator,
+                    right: Box::new(right),
+                }),
+            ));
+        }
+
+        Ok((rest, left))
+    }
+}
+
+/// Ranges of values, e.g. `a..b`, `a..=b`, `..b` or `a..`
+///
+/// Ranges do not chain, `a..b..c` is not a valid range.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Range<'a> {
+    pub span: &'a str,
+    pub start: Option<Box<FullExpression<'a>>>,
+    pub end: Option<Box<FullExpression<'a>>>,
+    /// true, if the range is written as `a..=b`
+    pub inclusive: bool,
+}
+
+/// parses either `..=` or `..`.
+/// Returns true, if the range is inclusive.
+pub(crate) fn range_operator(input: &str) -> Res<'_, bool> {
+    alt((
+        map(keywords::RangeInclusive::parse, |_| true),
+        map(keywords::Spread::parse, |_| false),
+    ))(input)
+}
+
+impl<'a> ParseExpression<'a> for Range<'a> {
+    fn parse(input: &'a str) -> Res<'a, FullExpression<'a>> {
+        use nom::combinator::{cut, opt};
+
+        // ..b
+        // ..=b
+        if let Ok((rest, inclusive)) = range_operator(input) {
+            let (rest, end) = Concat::parse_ws(rest)?;
+            let span = unsafe { from_to(input, rest) };
+
+            return Ok((
+                rest,
+                FullExpression::Range(Range {
+                    span,
+                    start: None,
+                    end: Some(Box::new(end)),
+                    inclusive,
+                }),
+            ));
+        }
+
+        let (rest, start) = Concat::parse(input)?;
+
+        if let Ok((rest, inclusive)) = ws(range_operator)(rest) {
+            // a..=b needs an upper bound, a.. may be left open
+            let (rest, end) = if inclusive {
+                map(cut(Concat::parse_ws), Some)(rest)?
+            } else {
+                opt(Concat::parse_ws)(rest)?
+            };
+
+            let span = unsafe { from_to(input, rest) };
+
+            return Ok((
+                rest,
+                FullExpression::Range(Range {
+                    span,
+                    start: Some(Box::new(start)),
+                    end: end.map(Box::new),
+                    inclusive,
+                }),
+            ));
+        }
+
+        Ok((rest, start))
+    }
+}
+
+/// Waits for the result of an asynchronous computation, e.g. `await fetch url`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Await<'a> {
+    pub span: &'a str,
+    pub expr: Box<FullExpression<'a>>,
+}
+
+impl<'a> ParseExpression<'a> for Await<'a> {
+    fn parse(input: &'a str) -> Res<'a, FullExpression<'a>> {
+        use nom::combinator::cut;
+
+        if let Ok((rest, _)) = keywords::Await::parse(input) {
+            let (rest, expr) = cut(Pipe::parse_ws)(rest)?;
+            let span = unsafe { from_to(input, rest) };
+
+            return Ok((
+                rest,
+                FullExpression::Await(Await {
+                    span,
+                    expr: Box::new(expr),
+                }),
+            ));
+        }
+
+        Pipe::parse(input)
+    }
+}
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Pipe<'a> {
@@ -174,7 +351,7 @@ pub struct Pipe<'a> {
 }
 
 impl<'a> ParseExpression<'a> for Pipe<'a> {
-    fn parse(input: &'a str) -> Res<'a, FullExpression> {
+    fn parse(input: &'a str) -> Res<'a, FullExpression<'a>> {
         use nom::{multi::many1, sequence::preceded};
 
         let (rest, expr) = Expression::parse(input)?;
@@ -212,7 +389,7 @@ impl<'a> From<Pipe<'a>> for FullExpression<'a> {
 mod tests {
     use super::*;
 
-    fn parse(input: &str) -> FullExpression {
+    fn parse(input: &str) -> FullExpression<'_> {
         FullExpression::parse_ws(input).unwrap().1
     }
 
@@ -277,6 +454,101 @@ mod tests {
         assert_eq!(rest, " ");
     }
 
+    #[test]
+    fn range_precedence() {
+        let input = "a..b + 1 ";
+        let (rest, expr) = FullExpression::parse(input).unwrap();
+        assert_eq!(
+            expr,
+            FullExpression::Range(Range {
+                span: "a..b + 1",
+                start: Some(Box::new(parse("a"))),
+                end: Some(Box::new(parse("b + 1"))),
+                inclusive: false,
+            })
+        );
+        assert_eq!(rest, " ");
+    }
+
+    #[test]
+    fn range_forms() {
+        let (_, expr) = FullExpression::parse("0..=9").unwrap();
+        assert!(matches!(
+            expr,
+            FullExpression::Range(Range {
+                start: Some(_),
+                end: Some(_),
+                inclusive: true,
+                ..
+            })
+        ));
+
+        let (_, expr) = FullExpression::parse("..n").unwrap();
+        assert!(matches!(
+            expr,
+            FullExpression::Range(Range {
+                start: None,
+                end: Some(_),
+                ..
+            })
+        ));
+
+        let (_, expr) = FullExpression::parse("n..").unwrap();
+        assert!(matches!(
+            expr,
+            FullExpression::Range(Range {
+                start: Some(_),
+                end: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn comparison() {
+        let input = "a + 1 >= b and c != d ";
+        let (rest, expr) = FullExpression::parse(input).unwrap();
+        assert_eq!(
+            expr,
+            FullExpression::And(And {
+                span: "a + 1 >= b and c != d",
+                left: Box::new(FullExpression::Comparison(Comparison {
+                    span: "a + 1 >= b",
+                    left: Box::new(parse("a + 1")),
+                    operator: ComparisonOperator::GreaterEqual,
+                    right: Box::new(parse("b")),
+                })),
+                right: Box::new(FullExpression::Comparison(Comparison {
+                    span: "c != d",
+                    left: Box::new(parse("c")),
+                    operator: ComparisonOperator::NotEqual,
+                    right: Box::new(parse("d")),
+                })),
+            })
+        );
+        assert_eq!(rest, " ");
+
+        for (input, operator) in [
+            ("x == y", ComparisonOperator::Equal),
+            ("x > y", ComparisonOperator::Greater),
+            ("x < y", ComparisonOperator::Smaller),
+            ("x <= y", ComparisonOperator::SmallerEqual),
+        ] {
+            let (rest, expr) = FullExpression::parse(input).unwrap();
+            assert_eq!(rest, "");
+            assert!(matches!(expr, FullExpression::Comparison(c) if c.operator == operator));
+        }
+
+        // comparisons do not chain
+        let (rest, _) = FullExpression::parse("a < b < c").unwrap();
+        assert_eq!(rest, " < c");
+    }
+
+    #[test]
+    fn range_inclusive_needs_end() {
+        assert!(FullExpression::parse("a..=").is_err());
+    }
+
     #[test]
     fn cheap_tests() {
         let input = [
@@ -302,6 +574,14 @@ mod tests {
             "list /> filter ft /> map n * 3 ++ [end_elem]",
             "cos x*2",
             "(cos x)*2",
+            "0..n",
+            "0..=9",
+            "..n",
+            "a..",
+            "0..n+1",
+            "(0..n) /> map double",
+            "if a do { 1 } else { 2 }",
+            "let x = if a do { 1 } else if b do { 2 } else { 3 } in x * 2",
         ];
 
         for i in input.iter() {
@@ -316,4 +596,29 @@ mod tests {
         let (rest, _expr) = FullExpression::parse(input).unwrap();
         assert_eq!(rest, "");
     }
+
+    #[test]
+    fn await_expr() {
+        let input = "await fetch url + 1";
+        let (rest, expr) = FullExpression::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            expr,
+            FullExpression::Add(Add {
+                span: input,
+                left: Box::new(FullExpression::Await(Await {
+                    span: "await fetch url",
+                    expr: Box::new(parse("fetch url")),
+                })),
+                right: Box::new(parse("1")),
+            })
+        );
+
+        let input = "await (await connect host) /> read";
+        let (rest, expr) = FullExpression::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert!(
+            matches!(expr, FullExpression::Await(a) if matches!(*a.expr, FullExpression::Pipe(_)))
+        );
+    }
 }
@@ -1,18 +1,18 @@
 use nom::combinator::cut;
 
 use crate::{
-    ast::{identifier::Identifier, keywords},
+    ast::keywords,
     parse::Res,
     util::{from_to, joined_by1},
     Parse,
 };
 
-use super::FullExpression;
+use super::{when::Guard, FullExpression};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct LetExpression<'a> {
     pub span: &'a str,
-    pub definitions: Vec<(Identifier<'a>, FullExpression<'a>)>,
+    pub definitions: Vec<(Guard<'a>, FullExpression<'a>)>,
     pub body: FullExpression<'a>,
 }
 
@@ -20,12 +20,12 @@ impl<'a> Parse<'a> for LetExpression<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         let (rest, _) = keywords::Let::parse(input)?;
 
-        fn item(s: &str) -> Res<'_, (Identifier<'_>, FullExpression<'_>)> {
-            let (rest, ident) = Identifier::parse_ws(s)?;
+        fn item(s: &str) -> Res<'_, (Guard<'_>, FullExpression<'_>)> {
+            let (rest, pattern) = Guard::parse_irrefutable_ws(s)?;
             let (rest, _) = cut(keywords::Assign::parse_ws)(rest)?;
             let (rest, expr) = cut(FullExpression::parse_ws)(rest)?;
 
-            Ok((rest, (ident, expr)))
+            Ok((rest, (pattern, expr)))
         }
 
         let (rest, definitions) = joined_by1(item, keywords::Comma::parse_ws)(rest)?;
@@ -62,7 +62,7 @@ mod tests {
             LetExpression {
                 span: input,
                 definitions: vec![(
-                    Identifier::parse_ws(" square").unwrap().1,
+                    Guard::parse_ws(" square").unwrap().1,
                     FullExpression::parse_ws(" x*x").unwrap().1
                 )],
                 body: FullExpression::parse_ws(" {something}").unwrap().1,
@@ -77,4 +77,37 @@ mod tests {
 
         assert_eq!(rest, "", "expect to consume all input");
     }
+
+    #[test]
+    fn let_in_destructuring() {
+        let input = "let (a, b) = pair, [first, rest..] = xs, Point x px = p in a + first";
+        let (rest, let_in_expression) = LetExpression::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume all input");
+
+        let patterns: Vec<_> = let_in_expression
+            .definitions
+            .into_iter()
+            .map(|(pattern, _)| pattern)
+            .collect();
+
+        assert!(matches!(patterns[0], Guard::TupleGuard(_)));
+        assert!(matches!(patterns[1], Guard::ArrayGuard(_)));
+        assert!(matches!(patterns[2], Guard::ObjectGuard(_)));
+    }
+
+    #[test]
+    fn let_in_refutable_patterns() {
+        for input in [
+            "let 7 = x in x",
+            "let -1 = x in x",
+            "let 0..9 = x in x",
+            "let a | b = x in a",
+            "let [a, b] = xs in a",
+            "let (a, 0) = pair in a",
+            "let Point x 0 = p in x",
+        ] {
+            assert!(LetExpression::parse(input).is_err(), "{}", input);
+        }
+    }
 }
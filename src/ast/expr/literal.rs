@@ -1,9 +1,10 @@
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while, take_while1},
-    character::complete::char,
-    combinator::{map, opt, recognize, value},
-    sequence::{preceded, tuple},
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, one_of, satisfy},
+    combinator::{map, not, opt, recognize, value},
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated, tuple},
 };
 
 use crate::{ast::*, parse::*, util::*};
@@ -11,9 +12,17 @@ use expr::StringLiteral;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Literal<'a> {
-    Bool { span: &'a str, value: bool },
+    Bool {
+        span: &'a str,
+        value: bool,
+    },
+    /// c'a', c'\n'
+    Char {
+        span: &'a str,
+        value: char,
+    },
     Int(Int<'a>),
-    Float(&'a str),
+    Float(Float<'a>),
     // TODO there are many places, where a String
     // gets created. What the fuck.
     StringLiteral(StringLiteral<'a>),
@@ -23,9 +32,10 @@ impl<'a> Literal<'a> {
     pub fn span(&'a self) -> &'a str {
         match self {
             Self::Bool { span, .. } => span,
+            Self::Char { span, .. } => span,
             Self::Int(i) => i.span,
             Self::StringLiteral(i) => i.span,
-            Self::Float(i) => i,
+            Self::Float(i) => i.span,
         }
     }
 }
@@ -48,6 +58,25 @@ mod literal_tests {
         );
     }
 
+    #[test]
+    fn char_literals() {
+        let inputs = [
+            ("c'a'", 'a'),
+            ("c'\\n'", '\n'),
+            ("c'ö'", 'ö'),
+            ("c'\\''", '\''),
+        ];
+        for (input, value) in inputs {
+            assert_eq!(
+                Literal::parse(input),
+                Ok(("", Literal::Char { span: input, value }))
+            );
+        }
+
+        assert!(Literal::parse("c''").is_err());
+        assert!(Literal::parse("c'ab'").is_err());
+    }
+
     #[test]
     fn bool_false() {
         assert_eq!(
@@ -67,14 +96,16 @@ impl<'a> Parse<'a> for Literal<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         alt((
             parse_boolean,
+            parse_char,
+            // 1.5 must not be parsed as the Int 1
+            map(Float::parse, Literal::Float),
             map(Int::parse, Literal::Int),
             map(StringLiteral::parse, Literal::StringLiteral),
-            map(parse_float, Literal::Float),
         ))(input)
     }
 }
 
-fn parse_boolean(input: &str) -> Res<Literal> {
+fn parse_boolean(input: &str) -> Res<'_, Literal<'_>> {
     use keywords::{False, True};
     let t = map(True::parse, |True { span }| Literal::Bool {
         span,
@@ -88,10 +119,24 @@ fn parse_boolean(input: &str) -> Res<Literal> {
     alt((t, f))(input)
 }
 
+fn parse_char(input: &str) -> Res<'_, Literal<'_>> {
+    use nom::character::complete::none_of;
+
+    let (rest, value) = delimited(
+        tag("c'"),
+        alt((super::string::parse_escape_codes, none_of("'\\"))),
+        char('\''),
+    )(input)?;
+    let span = unsafe { from_to(input, rest) };
+
+    Ok((rest, Literal::Char { span, value }))
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Int<'a> {
     pub span: &'a str,
     pub radix: u8,
+    /// digits without the prefix, may contain separators, e.g. 1_000
     pub digits: &'a str,
     // Int | Int64 | Int32 | Int16 | Int8
     // Uint | Uint64 | Uint32 | Uint16 | Uint8
@@ -111,32 +156,102 @@ pub enum IntTypeSuffix {
     Uint8,
 }
 
+impl IntTypeSuffix {
+    /// Largest value, that fits into the type
+    pub fn max(self) -> i128 {
+        use IntTypeSuffix::*;
+        match self {
+            Int => i64::MAX.into(),
+            Int32 => i32::MAX.into(),
+            Int16 => i16::MAX.into(),
+            Int8 => i8::MAX.into(),
+            Uint => u64::MAX.into(),
+            Uint32 => u32::MAX.into(),
+            Uint16 => u16::MAX.into(),
+            Uint8 => u8::MAX.into(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LiteralError {
+    /// The literal does not fit into its type, e.g. `300Uint8`
+    OutOfRange(IntTypeSuffix),
+    /// The literal is too large for its type, e.g. `1e39Float32`
+    FloatOutOfRange(FloatTypeSuffix),
+}
+
+impl std::fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiteralError::OutOfRange(ty) => write!(f, "literal out of range for {:?}", ty),
+            LiteralError::FloatOutOfRange(ty) => write!(f, "literal out of range for {:?}", ty),
+        }
+    }
+}
+
+impl std::error::Error for LiteralError {}
+
+impl<'a> Int<'a> {
+    /// Evaluates the literal.
+    /// Literals without a type suffix are of type Int.
+    pub fn value(&self) -> Result<i128, LiteralError> {
+        let ty = self.type_suffix.unwrap_or(IntTypeSuffix::Int);
+        let digits = self.digits.replace('_', "");
+
+        i128::from_str_radix(&digits, self.radix.into())
+            .ok()
+            .filter(|value| *value <= ty.max())
+            .ok_or(LiteralError::OutOfRange(ty))
+    }
+}
+
 fn number(c: char) -> bool {
     c.is_ascii_digit()
 }
 
-fn parse_type_suffix(input: &str) -> Res<Option<IntTypeSuffix>> {
+/// Digits, that may be separated by underscores, e.g. `1_000_000`
+/// Underscores may neither lead nor trail.
+fn digits(is_digit: fn(char) -> bool) -> impl Fn(&str) -> Res<'_, &str> {
+    move |input| {
+        recognize(pair(
+            take_while1(is_digit),
+            many0(pair(take_while1(|c| c == '_'), take_while1(is_digit))),
+        ))(input)
+    }
+}
+
+/// A type suffix, that ends the literal.
+/// `5Int8x` has no suffix, but is followed by `Int8x`.
+fn suffix<'a, T: Clone>(ty: T, name: &'static str) -> impl FnMut(&'a str) -> Res<'a, T> {
+    value(
+        ty,
+        terminated(
+            tag(name),
+            not(satisfy(|c| c.is_ascii_alphanumeric() || c == '_')),
+        ),
+    )
+}
+
+fn parse_type_suffix(input: &str) -> Res<'_, Option<IntTypeSuffix>> {
     use IntTypeSuffix::*;
     opt(alt((
-        value(Int, tag("Int")),
-        value(Int, tag("Int64")),
-        value(Int32, tag("Int32")),
-        value(Int16, tag("Int16")),
-        value(Int8, tag("Int8")),
-        value(Uint, tag("Uint")),
-        value(Uint, tag("Uint64")),
-        value(Uint32, tag("Uint32")),
-        value(Uint16, tag("Uint16")),
-        value(Uint8, tag("Uint8")),
+        suffix(Int, "Int64"),
+        suffix(Int32, "Int32"),
+        suffix(Int16, "Int16"),
+        suffix(Int8, "Int8"),
+        suffix(Int, "Int"),
+        suffix(Uint, "Uint64"),
+        suffix(Uint32, "Uint32"),
+        suffix(Uint16, "Uint16"),
+        suffix(Uint8, "Uint8"),
+        suffix(Uint, "Uint"),
     )))(input)
 }
 
 /// parses a hexadecimal number
-fn hex(i: &str) -> Res<Int> {
-    let (rest, digits) = preceded(
-        tag("0x"),
-        take_while1(|c| number(c) || ('A'..='F').contains(&c) || ('a'..='f').contains(&c)),
-    )(i)?;
+fn hex(i: &str) -> Res<'_, Int<'_>> {
+    let (rest, digits) = preceded(tag("0x"), digits(|c| c.is_ascii_hexdigit()))(i)?;
     let (rest, type_suffix) = parse_type_suffix(rest)?;
     let span = unsafe { from_to(i, rest) };
 
@@ -152,8 +267,8 @@ fn hex(i: &str) -> Res<Int> {
 }
 
 /// parses an octal number
-fn oct(i: &str) -> Res<Int> {
-    let (rest, digits) = preceded(tag("0o"), take_while1(|c| ('0'..='7').contains(&c)))(i)?;
+fn oct(i: &str) -> Res<'_, Int<'_>> {
+    let (rest, digits) = preceded(tag("0o"), digits(|c| ('0'..='7').contains(&c)))(i)?;
     let (rest, type_suffix) = parse_type_suffix(rest)?;
     let span = unsafe { from_to(i, rest) };
 
@@ -169,8 +284,8 @@ fn oct(i: &str) -> Res<Int> {
 }
 
 /// parses an binary number
-fn bin(i: &str) -> Res<Int> {
-    let (rest, digits) = preceded(tag("0b"), take_while1(|c| c == '0' || c == '1'))(i)?;
+fn bin(i: &str) -> Res<'_, Int<'_>> {
+    let (rest, digits) = preceded(tag("0b"), digits(|c| c == '0' || c == '1'))(i)?;
     let (rest, type_suffix) = parse_type_suffix(rest)?;
     let span = unsafe { from_to(i, rest) };
 
@@ -186,8 +301,8 @@ fn bin(i: &str) -> Res<Int> {
 }
 
 /// parses a decimal
-fn dec(i: &str) -> Res<Int> {
-    let (rest, digits) = take_while1(number)(i)?;
+fn dec(i: &str) -> Res<'_, Int<'_>> {
+    let (rest, digits) = digits(number)(i)?;
     let (rest, type_suffix) = parse_type_suffix(rest)?;
     let span = unsafe { from_to(i, rest) };
 
@@ -208,16 +323,79 @@ impl<'a> Parse<'a> for Int<'a> {
     }
 }
 
-fn parse_float(input: &str) -> Res<'_, &str> {
-    let neg = opt(char('-'));
-    let num = take_while1(number);
-    let dot = char('.');
-    // may be zero length, e.g. [1., 2., 3.]
-    let second_num = take_while(number);
+/// e.g. 3.14, 1., 1_000.5, 6.022e+23, 1e-9, 0.5Float32
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Float<'a> {
+    pub span: &'a str,
+    /// the number without its type suffix, may contain separators
+    pub digits: &'a str,
+    // Float | Float64 | Float32
+    pub type_suffix: Option<FloatTypeSuffix>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FloatTypeSuffix {
+    // Floats are 64 bits per default
+    Float,
+    Float32,
+}
+
+impl<'a> Float<'a> {
+    /// Evaluates the literal.
+    /// A Float32 is rounded to 32 bits of precision.
+    pub fn value(&self) -> Result<f64, LiteralError> {
+        let ty = self.type_suffix.unwrap_or(FloatTypeSuffix::Float);
+        let digits = self.digits.replace('_', "");
+
+        digits
+            .parse::<f64>()
+            .ok()
+            .map(|value| match ty {
+                FloatTypeSuffix::Float32 => value as f32 as f64,
+                FloatTypeSuffix::Float => value,
+            })
+            .filter(|value| value.is_finite())
+            .ok_or(LiteralError::FloatOutOfRange(ty))
+    }
+}
+
+impl<'a> Parse<'a> for Float<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let neg = opt(char('-'));
+        let num = digits(number);
+        // a second dot makes it a range, e.g. 1..5
+        let dot = terminated(char('.'), not(char('.')));
+        // may be zero length, e.g. [1., 2., 3.]
+        let second_num = opt(digits(number));
+        let exp = || tuple((char('e'), opt(one_of("+-")), digits(number)));
+
+        let (rest, digits) = recognize(tuple((
+            neg,
+            num,
+            alt((
+                recognize(pair(dot, pair(second_num, opt(exp())))),
+                // 1e9
+                recognize(exp()),
+            )),
+        )))(input)?;
+
+        let (rest, type_suffix) = opt(alt((
+            suffix(FloatTypeSuffix::Float, "Float64"),
+            suffix(FloatTypeSuffix::Float32, "Float32"),
+            suffix(FloatTypeSuffix::Float, "Float"),
+        )))(rest)?;
 
-    let exp = opt(tuple((char('e'), opt(char('-')), take_while1(number))));
+        let span = unsafe { from_to(input, rest) };
 
-    recognize(tuple((neg, num, dot, second_num, exp)))(input)
+        Ok((
+            rest,
+            Float {
+                span,
+                digits,
+                type_suffix,
+            },
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -225,11 +403,130 @@ mod float_tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn float_value() {
-        let inputs = ["3.14", "314.e-2", "-1.", "-1.0", "-1.0e20"];
-        for input in inputs.iter() {
-            let (rest, _) = parse_float(input).unwrap();
+        let inputs = [
+            ("3.14", 3.14),
+            ("314.e-2", 3.14),
+            ("-1.", -1.0),
+            ("-1.0", -1.0),
+            ("-1.0e20", -1.0e20),
+            ("6.022e+23", 6.022e23),
+            ("1e-9", 1e-9),
+            ("1_000.000_5", 1000.0005),
+            ("2.5Float64", 2.5),
+            ("0.1Float32", 0.1f32 as f64),
+        ];
+        for (input, value) in inputs {
+            let (rest, float) = Float::parse(input).unwrap();
             assert_eq!(rest, "");
+            assert_eq!(float.value(), Ok(value), "value of {}", input);
         }
+
+        assert_eq!(
+            Float::parse("1e39Float32").unwrap().1.value(),
+            Err(LiteralError::FloatOutOfRange(FloatTypeSuffix::Float32))
+        );
+        assert_eq!(
+            Float::parse("1e999").unwrap().1.value(),
+            Err(LiteralError::FloatOutOfRange(FloatTypeSuffix::Float))
+        );
+    }
+
+    #[test]
+    fn float_suffixes() {
+        let (_, float) = Float::parse("1.5Float32").unwrap();
+        assert_eq!(float.digits, "1.5");
+        assert_eq!(float.type_suffix, Some(FloatTypeSuffix::Float32));
+
+        let (_, float) = Float::parse("1.5Float").unwrap();
+        assert_eq!(float.type_suffix, Some(FloatTypeSuffix::Float));
+
+        // suffixes end the literal
+        let (rest, float) = Float::parse("1.5Float32x").unwrap();
+        assert_eq!(float.type_suffix, None);
+        assert_eq!(rest, "Float32x");
+    }
+
+    #[test]
+    fn not_floats() {
+        // ranges
+        assert!(Float::parse("1..5").is_err());
+        assert!(Float::parse("1..=5").is_err());
+        assert!(Float::parse("7").is_err());
+
+        assert_eq!(
+            Literal::parse("1.5"),
+            Ok(("", Literal::Float(Float::parse("1.5").unwrap().1)))
+        );
+        assert!(matches!(
+            Literal::parse("1..5"),
+            Ok(("..5", Literal::Int(_)))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod int_tests {
+    use super::*;
+
+    fn int(input: &str) -> Int<'_> {
+        let (rest, int) = Int::parse(input).unwrap();
+        assert_eq!(rest, "", "expect to consume all of {}", input);
+        int
+    }
+
+    #[test]
+    fn digit_separators() {
+        assert_eq!(int("1_000_000").value(), Ok(1_000_000));
+        assert_eq!(int("0xFF_FF").value(), Ok(0xFFFF));
+        assert_eq!(int("0b1010_1010").value(), Ok(0b1010_1010));
+        assert_eq!(int("0o7_7").value(), Ok(0o77));
+
+        // separators may not trail
+        assert_eq!(Int::parse("1_").unwrap().0, "_");
+    }
+
+    #[test]
+    fn type_suffixes() {
+        let inputs = [
+            ("5Int", IntTypeSuffix::Int),
+            ("5Int64", IntTypeSuffix::Int),
+            ("5Int32", IntTypeSuffix::Int32),
+            ("5Int8", IntTypeSuffix::Int8),
+            ("5Uint", IntTypeSuffix::Uint),
+            ("5Uint64", IntTypeSuffix::Uint),
+            ("5Uint16", IntTypeSuffix::Uint16),
+        ];
+        for (input, suffix) in inputs {
+            assert_eq!(int(input).type_suffix, Some(suffix));
+        }
+
+        // suffixes end the literal
+        for input in ["5Int8x", "5Int_", "5Uint16a", "0xFFUint8Z"] {
+            let (rest, int) = Int::parse(input).unwrap();
+            assert_eq!(int.type_suffix, None, "{}", input);
+            assert!(!rest.is_empty(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(int("255Uint8").value(), Ok(255));
+        assert_eq!(
+            int("256Uint8").value(),
+            Err(LiteralError::OutOfRange(IntTypeSuffix::Uint8))
+        );
+        assert_eq!(int("0x7FFF_FFFF_FFFF_FFFF").value(), Ok(i64::MAX.into()));
+        assert_eq!(
+            int("9_223_372_036_854_775_808").value(),
+            Err(LiteralError::OutOfRange(IntTypeSuffix::Int))
+        );
+        assert_eq!(int("18446744073709551615Uint").value(), Ok(u64::MAX.into()));
+        // too large, even for i128
+        assert_eq!(
+            int("999999999999999999999999999999999999999999").value(),
+            Err(LiteralError::OutOfRange(IntTypeSuffix::Int))
+        );
     }
 }
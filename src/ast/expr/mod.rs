@@ -2,6 +2,7 @@ pub mod block;
 pub mod closure;
 pub mod let_in;
 pub mod literal;
+pub mod record;
 pub mod string;
 pub mod when;
 pub use string::*;
@@ -9,12 +10,14 @@ pub mod full;
 pub use block::BlockExpression;
 pub use full::FullExpression;
 pub use literal::Literal;
+pub use record::*;
 
 use nom::{
     branch::alt,
-    combinator::{map, opt},
+    character::complete::{one_of, space0, space1},
+    combinator::{map, not, opt},
     multi::{many0, separated_list0},
-    sequence::{delimited, terminated},
+    sequence::{delimited, pair, preceded, separated_pair, terminated},
 };
 
 use crate::ast::identifier::{Identifier, IdentifierPath};
@@ -43,6 +46,10 @@ impl<'a> Expression<'a> {
 impl<'a> Parse<'a> for Expression<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         alt((
+            // `Point x=1` would otherwise be a function call with named arguments
+            map(StructConstruction::parse, |s| {
+                Expression::Value(Value::StructConstruction(s))
+            }),
             map(FunctionCall::parse, Expression::FunctionCall),
             map(Value::parse, Expression::Value),
         ))(input)
@@ -53,7 +60,7 @@ impl<'a> Parse<'a> for Expression<'a> {
 mod value_tests {
     use super::*;
 
-    fn parse(input: &str) -> Value {
+    fn parse(input: &str) -> Value<'_> {
         Value::parse_ws(input).unwrap().1
     }
 
@@ -98,10 +105,16 @@ pub enum Value<'a> {
     FullIdentifier(IdentifierPath<'a>),
     Closure(closure::Closure<'a>),
     Array(Array<'a>),
+    MapLiteral(MapLiteral<'a>),
+    SetLiteral(SetLiteral<'a>),
     Abs(Abs<'a>),
     /// Tuples are, more often than not, just expressions wrapped in Parenthesis
     Tuple(Tuple<'a>),
     When(When<'a>),
+    /// if-else used as an expression. Always has an else clause.
+    If(block::If<'a>),
+    StructConstruction(StructConstruction<'a>),
+    RecordUpdate(RecordUpdate<'a>),
     BlockExpression(BlockExpression<'a>),
 
     // √x^2    == (√x)^2
@@ -121,13 +134,69 @@ impl<'a> Value<'a> {
             Self::FullIdentifier(l) => l.span,
             Self::Closure(l) => l.span,
             Self::Array(l) => l.span,
+            Self::MapLiteral(l) => l.span,
+            Self::SetLiteral(l) => l.span,
             Self::Tuple(l) => l.span,
             Self::Abs(l) => l.span,
             Self::BlockExpression(l) => l.span,
             Self::Sqrt(l) => l.span,
             Self::Not(l) => l.span,
             Self::Power(l) => l.span,
-            Self::When(l) => l.span,
+            Self::When(l) => l.span(),
+            Self::If(l) => l.span,
+            Self::StructConstruction(l) => l.span,
+            Self::RecordUpdate(l) => l.span,
+        }
+    }
+}
+
+impl<'a> Value<'a> {
+    /// Parses the values in curly braces: blocks, record updates, maps and sets.
+    ///
+    /// What follows the first element decides between them, e.g. `{ a with`, `{ a:` or `{ a,`.
+    /// Trying one after another would parse nested braces over and over again,
+    /// which takes exponential time.
+    fn parse_curly(input: &'a str) -> Res<'a, Self> {
+        use block::BlockExpressionPart;
+        use keywords::{Comma, CurlyOpen, TypeHint, With};
+
+        let (rest, _) = CurlyOpen::parse(input)?;
+
+        let (after_first, first) = match BlockExpressionPart::parse_ws(rest) {
+            Ok(result) => result,
+            // {}, {:} and {,}
+            Err(nom::Err::Error(_)) => {
+                return alt((
+                    map(BlockExpression::parse, Value::BlockExpression),
+                    map(MapLiteral::parse, Value::MapLiteral),
+                    map(SetLiteral::parse, Value::SetLiteral),
+                ))(input)
+            }
+            Err(e) => return Err(e),
+        };
+
+        // statements like `let` can only be part of a block
+        let first = match first {
+            BlockExpressionPart::FullExpression(first) => first,
+            first => {
+                let (rest, block) = BlockExpression::parse_rest(input, after_first, first)?;
+                return Ok((rest, Value::BlockExpression(block)));
+            }
+        };
+
+        if With::parse_ws(after_first).is_ok() {
+            let (rest, update) = commit(RecordUpdate::parse_rest(input, after_first, first))?;
+            Ok((rest, Value::RecordUpdate(update)))
+        } else if TypeHint::parse_ws(after_first).is_ok() {
+            let (rest, map) = commit(MapLiteral::parse_rest(input, after_first, first))?;
+            Ok((rest, Value::MapLiteral(map)))
+        } else if Comma::parse_ws(after_first).is_ok() {
+            let (rest, set) = commit(SetLiteral::parse_rest(input, after_first, first))?;
+            Ok((rest, Value::SetLiteral(set)))
+        } else {
+            let first = BlockExpressionPart::FullExpression(first);
+            let (rest, block) = BlockExpression::parse_rest(input, after_first, first)?;
+            Ok((rest, Value::BlockExpression(block)))
         }
     }
 }
@@ -137,13 +206,15 @@ impl<'a> Parse<'a> for Value<'a> {
         let (rest, value) = alt((
             map(Literal::parse, Value::Literal),
             map(IString::parse, Value::IString),
+            map(StructConstruction::parse, Value::StructConstruction),
             map(IdentifierPath::parse, Value::FullIdentifier),
             map(closure::Closure::parse, Value::Closure),
             map(Array::parse, Value::Array),
             map(Abs::parse, Value::Abs),
             map(Tuple::parse, Value::Tuple),
             map(When::parse, Value::When),
-            map(BlockExpression::parse, Value::BlockExpression),
+            map(block::If::parse_expression, Value::If),
+            Value::parse_curly,
             // unary expressions
             map(Sqrt::parse, Value::Sqrt),
             map(Not::parse, Value::Not),
@@ -257,7 +328,7 @@ impl<'a> Parse<'a> for Tuple<'a> {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Array<'a> {
     pub span: &'a str,
-    pub values: Vec<FullExpression<'a>>,
+    pub values: Vec<ArrayElement<'a>>,
 }
 
 impl<'a> Parse<'a> for Array<'a> {
@@ -265,7 +336,7 @@ impl<'a> Parse<'a> for Array<'a> {
         let (rest, values) = delimited(
             keywords::BracketOpen::parse,
             terminated(
-                separated_list0(keywords::Comma::parse_ws, FullExpression::parse_ws),
+                separated_list0(keywords::Comma::parse_ws, ArrayElement::parse_ws),
                 opt(keywords::Comma::parse_ws),
             ),
             keywords::BracketClose::parse_ws,
@@ -276,19 +347,188 @@ impl<'a> Parse<'a> for Array<'a> {
     }
 }
 
+/// e.g. the `0`, `..xs` and `9` in `[0, ..xs, 9]`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ArrayElement<'a> {
+    Expression(FullExpression<'a>),
+    Spread(Spread<'a>),
+}
+
+impl<'a> ArrayElement<'a> {
+    pub fn span(&'a self) -> &'a str {
+        match self {
+            Self::Expression(e) => e.span(),
+            Self::Spread(s) => s.span,
+        }
+    }
+}
+
+impl<'a> Parse<'a> for ArrayElement<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        // At the start of an element `..xs` is always a spread,
+        // as there is no value in front of it. Use `[(..5)]` for a range.
+        alt((
+            map(Spread::parse, ArrayElement::Spread),
+            map(FullExpression::parse, ArrayElement::Expression),
+        ))(input)
+    }
+}
+
+/// Inserts all elements of a collection, e.g. `..xs` or `.. xs`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Spread<'a> {
+    pub span: &'a str,
+    pub expr: FullExpression<'a>,
+}
+
+impl<'a> Parse<'a> for Spread<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, expr) = preceded(keywords::Spread::parse, FullExpression::parse_ws)(input)?;
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, Spread { span, expr }))
+    }
+}
+
+/// { "a": 1, "b": 2 }
+/// {:} is the empty map, as {} is an empty block
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MapLiteral<'a> {
+    pub span: &'a str,
+    pub entries: Vec<(FullExpression<'a>, FullExpression<'a>)>,
+}
+
+impl<'a> Parse<'a> for MapLiteral<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        use keywords::{CurlyClose, CurlyOpen, TypeHint};
+
+        let (rest, _) = CurlyOpen::parse(input)?;
+
+        // {:}
+        if let Ok((rest, _)) = pair(TypeHint::parse_ws, CurlyClose::parse_ws)(rest) {
+            let span = unsafe { from_to(input, rest) };
+            let entries = Vec::new();
+            return Ok((rest, MapLiteral { span, entries }));
+        }
+
+        let (rest, first_key) = FullExpression::parse_ws(rest)?;
+
+        MapLiteral::parse_rest(input, rest, first_key)
+    }
+}
+
+impl<'a> MapLiteral<'a> {
+    /// Parses the rest of a map after its first key, starting at the `:`.
+    /// `input` starts at the opening curly brace.
+    pub(crate) fn parse_rest(
+        input: &'a str,
+        rest: &'a str,
+        first_key: FullExpression<'a>,
+    ) -> Res<'a, Self> {
+        use keywords::{Comma, CurlyClose, TypeHint};
+
+        let entry = |i| {
+            separated_pair(
+                FullExpression::parse_ws,
+                TypeHint::parse_ws,
+                FullExpression::parse_ws,
+            )(i)
+        };
+
+        let (rest, first_value) = preceded(TypeHint::parse_ws, FullExpression::parse_ws)(rest)?;
+        let (rest, others) = opt(preceded(
+            Comma::parse_ws,
+            joined_by0(entry, Comma::parse_ws),
+        ))(rest)?;
+        let (rest, _) = CurlyClose::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+        let entries = std::iter::once((first_key, first_value))
+            .chain(others.unwrap_or_default())
+            .collect();
+
+        Ok((rest, MapLiteral { span, entries }))
+    }
+}
+
+/// { 1, 2, 3 }
+/// A set with a single element needs a trailing comma, e.g. { x, }
+/// Otherwise it is a block.
+/// {,} is the empty set
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetLiteral<'a> {
+    pub span: &'a str,
+    pub values: Vec<FullExpression<'a>>,
+}
+
+impl<'a> Parse<'a> for SetLiteral<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        use keywords::{Comma, CurlyClose, CurlyOpen};
+
+        let (rest, _) = CurlyOpen::parse(input)?;
+
+        // {,}
+        if let Ok((rest, _)) = pair(Comma::parse_ws, CurlyClose::parse_ws)(rest) {
+            let span = unsafe { from_to(input, rest) };
+            let values = Vec::new();
+            return Ok((rest, SetLiteral { span, values }));
+        }
+
+        let (rest, first) = FullExpression::parse_ws(rest)?;
+
+        SetLiteral::parse_rest(input, rest, first)
+    }
+}
+
+impl<'a> SetLiteral<'a> {
+    /// Parses the rest of a set after its first value, starting at the `,`.
+    /// `input` starts at the opening curly brace.
+    pub(crate) fn parse_rest(
+        input: &'a str,
+        rest: &'a str,
+        first: FullExpression<'a>,
+    ) -> Res<'a, Self> {
+        use keywords::{Comma, CurlyClose};
+
+        let (rest, _) = Comma::parse_ws(rest)?;
+        let (rest, others) = joined_by0(FullExpression::parse_ws, Comma::parse_ws)(rest)?;
+        let (rest, _) = CurlyClose::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+        let values = std::iter::once(first).chain(others).collect();
+
+        Ok((rest, SetLiteral { span, values }))
+    }
+}
+
 // Note: may as well be a variable instaed of a function name
 // Note: may be field access. Currently there is no distinction in the parser.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FunctionCall<'a> {
     pub span: &'a str,
     pub function_name: IdentifierPath<'a>,
+    /// Explicit generic arguments, e.g. `parse @Int "7"`
+    pub type_args: Vec<Type<'a>>,
     pub args: Vec<FunctionArg<'a>>,
 }
 
 impl<'a> Parse<'a> for FunctionCall<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         let (rest, function_name) = IdentifierPath::parse(input)?;
-        let (rest, args) = many0(FunctionArg::parse_ws)(rest)?;
+        // `@` must stay on the line of the call,
+        // otherwise it would swallow the attributes of the next item.
+        let (rest, type_args) = many0(preceded(
+            pair(space0, keywords::At::parse),
+            ty::parse_generic_arg,
+        ))(rest)?;
+        // Arguments end at the line break, so that the next line
+        // may start with a new statement, e.g. `n += 1` or `x = 1`.
+        // `sum ..xs` passes a spread argument, as the `..` has whitespace only in front of it.
+        // `a..b`, `a .. b` and `a.. b` are ranges.
+        let (rest, args) = many0(alt((
+            preceded(space1, FunctionArg::parse),
+            preceded(not(keywords::Spread::parse), FunctionArg::parse),
+        )))(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
@@ -297,6 +537,7 @@ impl<'a> Parse<'a> for FunctionCall<'a> {
             FunctionCall {
                 span,
                 function_name,
+                type_args,
                 args,
             },
         ))
@@ -307,17 +548,50 @@ impl<'a> Parse<'a> for FunctionCall<'a> {
 pub struct FunctionArg<'a> {
     pub span: &'a str,
     pub name: Option<Identifier<'a>>,
+    /// Passes all elements of the value as separate arguments, e.g. `sum ..xs`
+    pub spread: bool,
     pub value: Value<'a>,
 }
 
 impl<'a> Parse<'a> for FunctionArg<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, name) = opt(terminated(Identifier::parse, keywords::Assign::parse_ws))(input)?;
-        let (rest, value) = Value::parse_ws(rest)?;
+        // ..xs
+        // The value must directly follow the `..`, otherwise it is a range.
+        if let Ok((rest, value)) = preceded(keywords::Spread::parse, Value::parse)(input) {
+            let span = unsafe { from_to(input, rest) };
+            return Ok((
+                rest,
+                FunctionArg {
+                    span,
+                    name: None,
+                    spread: true,
+                    value,
+                },
+            ));
+        }
+
+        // `x == y` and `x => y` do not name an argument
+        let (rest, name) = opt(terminated(
+            Identifier::parse,
+            terminated(keywords::Assign::parse_ws, not(one_of("=>"))),
+        ))(input)?;
+        // only named arguments may have whitespace in front of the value, e.g. `x = 1`
+        let (rest, value) = match name {
+            Some(_) => Value::parse_ws(rest)?,
+            None => Value::parse(rest)?,
+        };
 
         let span = unsafe { from_to(input, rest) };
 
-        Ok((rest, FunctionArg { span, name, value }))
+        Ok((
+            rest,
+            FunctionArg {
+                span,
+                name,
+                spread: false,
+                value,
+            },
+        ))
     }
 }
 
@@ -339,5 +613,193 @@ mod tests {
     }
     derive_tests!(Abs, abs_expr, ["|x|", "|[1, 2, 3]|"]);
 
-    derive_tests!(Array, arrays, ["[]", "[1]", "[ 1,2,3 ]", "[1, 2, ]"]);
+    #[test]
+    fn string_and_char_arguments() {
+        let input = "write c'x' r\"C:\\$dir\" \"\"\"\n  text\n  \"\"\"";
+        let (rest, call) = FunctionCall::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(call.function_name, IdentifierPath::from_str("write"));
+
+        let values: Vec<_> = call.args.iter().map(|arg| &arg.value).collect();
+        assert!(matches!(
+            values[0],
+            Value::Literal(Literal::Char { value: 'x', .. })
+        ));
+        assert!(
+            matches!(values[1], Value::Literal(Literal::StringLiteral(s)) if s.value == "C:\\$dir")
+        );
+        assert!(
+            matches!(values[2], Value::Literal(Literal::StringLiteral(s)) if s.value == "text")
+        );
+
+        // only c' starts a char, so this calls `c`
+        let (rest, call) = FunctionCall::parse("c\"abc\"").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(call.function_name, IdentifierPath::from_str("c"));
+        assert_eq!(call.args[0].value, Value::from_str("\"abc\""));
+    }
+
+    derive_tests!(
+        Array,
+        arrays,
+        [
+            "[]",
+            "[1]",
+            "[ 1,2,3 ]",
+            "[1, 2, ]",
+            "[0, ..xs, 9]",
+            "[..a, ..b]"
+        ]
+    );
+
+    derive_tests!(
+        MapLiteral,
+        map_literals,
+        [
+            "{:}",
+            "{ \"a\": 1 }",
+            "{ \"a\": 1, \"b\": 2, }",
+            "{ key x: f x }"
+        ]
+    );
+
+    derive_tests!(
+        SetLiteral,
+        set_literals,
+        ["{,}", "{ , }", "{ 1, }", "{1, 2, 3}", "{ a, b + 1, }"]
+    );
+
+    #[test]
+    fn curly_values() {
+        let map = match Value::from_str("{ \"a\": 1, \"b\": 2 }") {
+            Value::MapLiteral(map) => map,
+            other => panic!("expected a map literal, got {:?}", other),
+        };
+        assert_eq!(
+            map.entries[1],
+            (
+                FullExpression::from_str("\"b\""),
+                FullExpression::from_str("2")
+            )
+        );
+
+        assert!(matches!(Value::from_str("{:}"), Value::MapLiteral(m) if m.entries.is_empty()));
+        assert!(matches!(Value::from_str("{ a, b }"), Value::SetLiteral(_)));
+        assert!(matches!(Value::from_str("{ a, }"), Value::SetLiteral(_)));
+        assert!(matches!(
+            Value::from_str("{ a }"),
+            Value::BlockExpression(_)
+        ));
+        assert!(matches!(Value::from_str("{}"), Value::BlockExpression(_)));
+        assert!(matches!(Value::from_str("{,}"), Value::SetLiteral(s) if s.values.is_empty()));
+        assert!(matches!(
+            Value::from_str("{ let x = 1; { x, } }"),
+            Value::BlockExpression(_)
+        ));
+        assert!(matches!(
+            Value::from_str("{ { a: 1 }: { b, }, }"),
+            Value::MapLiteral(_)
+        ));
+
+        // after the first element the kind is settled
+        for input in ["{ a: }", "{ a, ; }", "{ p with }"] {
+            assert!(
+                matches!(Value::parse(input), Err(nom::Err::Failure(_))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn deeply_nested_curly_values() {
+        // every level used to be parsed by each kind of curly value in turn
+        let depth = 12;
+        let input = format!("{}1{}", "{ ".repeat(depth), " }".repeat(depth));
+        let (rest, _) = Value::parse(&input).unwrap();
+        assert_eq!(rest, "");
+
+        let input = format!("{}1{}", "{ ".repeat(depth), ", }".repeat(depth));
+        let (rest, value) = Value::parse(&input).unwrap();
+        assert_eq!(rest, "");
+        assert!(matches!(value, Value::SetLiteral(_)));
+    }
+
+    #[test]
+    fn spread_array() {
+        let (rest, array) = Array::parse("[0, ..xs, (..5)]").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            array.values[1],
+            ArrayElement::Spread(Spread {
+                span: "..xs",
+                expr: FullExpression::from_str("xs"),
+            })
+        );
+        assert!(matches!(array.values[2], ArrayElement::Expression(_)));
+
+        // the whitespace after the .. does not matter
+        let (rest, array) = Array::parse("[.. xs, ..ys]").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            array.values[0],
+            ArrayElement::Spread(Spread {
+                span: ".. xs",
+                expr: FullExpression::from_str("xs"),
+            })
+        );
+        assert!(matches!(array.values[1], ArrayElement::Spread(_)));
+
+        // ranges need a value in front of the ..
+        for input in ["[a..b]", "[a .. b]", "[..=5]"] {
+            let (rest, array) = Array::parse(input).unwrap();
+            assert_eq!(rest, "");
+            assert!(matches!(array.values[0], ArrayElement::Expression(_)));
+        }
+    }
+
+    #[test]
+    fn spread_argument() {
+        let (rest, call) = FunctionCall::parse("sum 1 ..xs").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(call.args.len(), 2);
+        assert!(!call.args[0].spread);
+        assert_eq!(
+            call.args[1],
+            FunctionArg {
+                span: "..xs",
+                name: None,
+                spread: true,
+                value: Value::from_str("xs"),
+            }
+        );
+
+        let (rest, call) = FunctionCall::parse("sum ..xs").unwrap();
+        assert_eq!(rest, "");
+        assert!(call.args[0].spread);
+
+        // a parenthesized range is a regular argument
+        let (rest, call) = FunctionCall::parse("take (..5)").unwrap();
+        assert_eq!(rest, "");
+        assert!(!call.args[0].spread);
+
+        // these are ranges, not spread arguments
+        for input in ["a..b", "a .. b", "a.. b", "a ..= b", "a ..=b"] {
+            let (rest, call) = FunctionCall::parse(input).unwrap();
+            assert!(call.args.is_empty());
+            assert_ne!(rest, "");
+        }
+    }
+
+    #[test]
+    fn type_application() {
+        let input = "convert @Int @(List String) x";
+        let (rest, call) = FunctionCall::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            call.type_args,
+            vec![Type::from_str("Int"), Type::from_str("List String")]
+        );
+        assert_eq!(call.args.len(), 1);
+    }
 }
@@ -0,0 +1,232 @@
+use nom::{
+    branch::alt,
+    character::complete::space0,
+    combinator::verify,
+    multi::many1,
+    sequence::{delimited, preceded, terminated},
+};
+
+use crate::{
+    ast::{identifier::*, keywords},
+    parse::Res,
+    util::{from_to, joined_by1},
+    Parse,
+};
+
+use super::{FullExpression, Value};
+
+/// Builds a value of a struct.
+/// e.g.
+/// Point x=1 y=2
+/// Point { x = 1, y = 2 }
+/// shapes.Circle { radius = 3 }
+///
+/// The name of the struct must be capitalized.
+/// Otherwise `point x=1 y=2` is a function call with named arguments.
+/// The braces need at least one field, `Foo {}` calls `Foo` with an empty block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StructConstruction<'a> {
+    pub span: &'a str,
+    pub struct_identifier: IdentifierPath<'a>,
+    pub fields: Vec<FieldInit<'a>>,
+}
+
+impl<'a> Parse<'a> for StructConstruction<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, struct_identifier) = verify(IdentifierPath::parse, |path| {
+            path.value
+                .last()
+                .is_some_and(|name| name.value.starts_with(|c: char| c.is_ascii_uppercase()))
+        })(input)?;
+
+        let (rest, fields) = alt((
+            // { x = 1, y = 2 }
+            delimited(
+                keywords::CurlyOpen::parse_ws,
+                joined_by1(FieldInit::parse_ws, keywords::Comma::parse_ws),
+                keywords::CurlyClose::parse_ws,
+            ),
+            // x=1 y=2
+            // just like function arguments, these end at the line break
+            many1(preceded(space0, FieldInit::parse_value)),
+        ))(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((
+            rest,
+            StructConstruction {
+                span,
+                struct_identifier,
+                fields,
+            },
+        ))
+    }
+}
+
+/// Copies a struct value, replacing some of its fields.
+/// e.g.
+/// { p with x = 3 }
+/// { config with port = 8080, host = "localhost" }
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordUpdate<'a> {
+    pub span: &'a str,
+    pub base: Box<FullExpression<'a>>,
+    pub fields: Vec<FieldInit<'a>>,
+}
+
+impl<'a> Parse<'a> for RecordUpdate<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, _) = keywords::CurlyOpen::parse(input)?;
+        let (rest, base) = FullExpression::parse_ws(rest)?;
+
+        RecordUpdate::parse_rest(input, rest, base)
+    }
+}
+
+impl<'a> RecordUpdate<'a> {
+    /// Parses the rest of a record update after its base, starting at `with`.
+    /// `input` starts at the opening curly brace.
+    pub(crate) fn parse_rest(
+        input: &'a str,
+        rest: &'a str,
+        base: FullExpression<'a>,
+    ) -> Res<'a, Self> {
+        let (rest, _) = keywords::With::parse_ws(rest)?;
+        let (rest, fields) = joined_by1(FieldInit::parse_ws, keywords::Comma::parse_ws)(rest)?;
+        let (rest, _) = keywords::CurlyClose::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((
+            rest,
+            RecordUpdate {
+                span,
+                base: Box::new(base),
+                fields,
+            },
+        ))
+    }
+}
+
+/// A single field of a struct construction or record update, e.g. `x = 1`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldInit<'a> {
+    pub span: &'a str,
+    pub name: Identifier<'a>,
+    pub value: FullExpression<'a>,
+}
+
+impl<'a> FieldInit<'a> {
+    /// Parses a field without braces around it, e.g. the `y=2` in `Point x=1 y=2`.
+    /// Just like function arguments, the value must be a single `Value`.
+    fn parse_value(input: &'a str) -> Res<'a, Self> {
+        let (rest, name) = terminated(Identifier::parse, keywords::Assign::parse_ws)(input)?;
+        let (rest, value) = Value::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+        let value = FullExpression::Expression(Box::new(super::Expression::Value(value)));
+
+        Ok((rest, FieldInit { span, name, value }))
+    }
+}
+
+impl<'a> Parse<'a> for FieldInit<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, name) = Identifier::parse(input)?;
+        let (rest, _) = keywords::Assign::parse_ws(rest)?;
+        let (rest, value) = FullExpression::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, FieldInit { span, name, value }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braced_construction() {
+        let input = "Point { x = 1, y = 2 + 3, }";
+        let (rest, got) = StructConstruction::parse(input).unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(
+            got,
+            StructConstruction {
+                span: input,
+                struct_identifier: IdentifierPath::from_str("Point"),
+                fields: vec![
+                    FieldInit {
+                        span: "x = 1",
+                        name: Identifier::from_str("x"),
+                        value: FullExpression::from_str("1"),
+                    },
+                    FieldInit {
+                        span: "y = 2 + 3",
+                        name: Identifier::from_str("y"),
+                        value: FullExpression::from_str("2 + 3"),
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn juxtaposed_construction() {
+        let input = "shapes.Point x=1 y=(a * 2)";
+        let (rest, got) = StructConstruction::parse(input).unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(
+            got.struct_identifier,
+            IdentifierPath::from_str("shapes.Point")
+        );
+        assert_eq!(got.fields.len(), 2);
+        assert_eq!(got.fields[0].span, "x=1");
+        assert_eq!(got.fields[1].span, "y=(a * 2)");
+
+        // lowercase names are function calls
+        assert!(StructConstruction::parse("point x=1").is_err());
+        // a capitalized name alone is just an identifier
+        assert!(StructConstruction::parse("None").is_err());
+    }
+
+    #[test]
+    fn record_update() {
+        let input = "{ p with x = 3, y = p.y + 1 }";
+        let (rest, got) = RecordUpdate::parse(input).unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(*got.base, FullExpression::from_str("p"));
+        assert_eq!(got.fields.len(), 2);
+        assert_eq!(got.fields[1].span, "y = p.y + 1");
+    }
+
+    #[test]
+    fn as_values() {
+        let value = Value::from_str("{ p with x = 3 }");
+        assert!(matches!(value, Value::RecordUpdate(_)));
+
+        let value = Value::from_str("{ p }");
+        assert!(matches!(value, Value::BlockExpression(_)));
+
+        let expr = FullExpression::from_str("Message from=sender text='hello'");
+        assert!(matches!(
+            expr,
+            FullExpression::Expression(e)
+                if matches!(*e, super::super::Expression::Value(Value::StructConstruction(_)))
+        ));
+
+        // no fields, so this is a call with an empty block
+        assert!(StructConstruction::parse("Foo {}").is_err());
+        let expr = FullExpression::from_str("Foo {}");
+        assert!(matches!(
+            expr,
+            FullExpression::Expression(e)
+                if matches!(*e, super::super::Expression::FunctionCall(_))
+        ));
+    }
+}
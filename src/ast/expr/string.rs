@@ -2,12 +2,20 @@ use crate::{ast::*, parse::*, util::*};
 use expr::FullExpression;
 use nom::{
     branch::alt,
-    bytes::complete::is_not,
+    bytes::complete::{is_not, tag, take_until, take_while},
+    character::complete::char,
     combinator::{map, value, verify},
     multi::many0,
     sequence::{delimited, preceded},
 };
 
+/// String without any inline expressions.
+/// e.g.
+/// 'hello\n'
+/// r"C:\path\$x"
+/// """
+///     multi-line text
+/// """
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StringLiteral<'a> {
     pub span: &'a str,
@@ -16,30 +24,98 @@ pub struct StringLiteral<'a> {
 
 impl<'a> Parse<'a> for StringLiteral<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, string) = verify(IString::parse, |istr| {
-            for part in istr.parts.iter() {
-                match part {
-                    StringPart::InlineExpression(_) => return false,
-                    _ => continue,
-                }
-            }
+        alt((parse_raw, parse_text_block, parse_escaped))(input)
+    }
+}
+
+/// r"C:\path\$x" or r'C:\path\$x'
+/// Neither escape codes nor inline expressions are interpreted.
+fn parse_raw(input: &str) -> Res<'_, StringLiteral<'_>> {
+    let (rest, value) = preceded(
+        char('r'),
+        alt((
+            delimited(char('"'), take_while(|c| c != '"'), char('"')),
+            delimited(char('\''), take_while(|c| c != '\''), char('\'')),
+        )),
+    )(input)?;
+
+    let span = unsafe { from_to(input, rest) };
+    let value = value.to_string();
 
-            true
-        })(input)?;
+    Ok((rest, StringLiteral { span, value }))
+}
+
+/// Multi-line string delimited by triple quotes.
+/// The content is taken verbatim, except for its indentation, see `strip_indentation`.
+fn parse_text_block(input: &str) -> Res<'_, StringLiteral<'_>> {
+    let quotes = || tag("\"\"\"");
+    let (rest, content) = delimited(quotes(), take_until("\"\"\""), quotes())(input)?;
+
+    let span = unsafe { from_to(input, rest) };
+    let value = strip_indentation(content);
 
-        let IString { span, parts } = string;
+    Ok((rest, StringLiteral { span, value }))
+}
+
+/// Removes the indentation shared by all lines of a text block.
+///
+/// The line break right after the opening quotes is dropped,
+/// as well as a last line made up of whitespace only.
+/// The indentation of that last line, in front of the closing quotes,
+/// counts towards the shared indentation.
+fn strip_indentation(content: &str) -> String {
+    let indentation = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    let content = content.strip_prefix('\n').unwrap_or(content);
+    let mut lines: Vec<&str> = content.split('\n').collect();
+
+    let closing_line = match lines.last() {
+        Some(last) if lines.len() > 1 && is_blank(last) => lines.pop(),
+        _ => None,
+    };
+
+    let shared = lines
+        .iter()
+        .copied()
+        .filter(|line| !is_blank(line))
+        .chain(closing_line)
+        .map(indentation)
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| if is_blank(line) { "" } else { &line[shared..] })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-        let mut value = String::new();
-        for part in parts {
+/// '...' or "...", but without inline expressions
+fn parse_escaped(input: &str) -> Res<'_, StringLiteral<'_>> {
+    let (rest, string) = verify(IString::parse, |istr| {
+        for part in istr.parts.iter() {
             match part {
-                StringPart::InlineExpression(_) => unreachable!(),
-                StringPart::Char(c) => value.push(c),
-                StringPart::Literal(s) => value.push_str(s),
+                StringPart::InlineExpression(_) => return false,
+                _ => continue,
             }
         }
 
-        Ok((rest, StringLiteral { span, value }))
+        true
+    })(input)?;
+
+    let IString { span, parts } = string;
+
+    let mut value = String::new();
+    for part in parts {
+        match part {
+            StringPart::InlineExpression(_) => unreachable!(),
+            StringPart::Char(c) => value.push(c),
+            StringPart::Literal(s) => value.push_str(s),
+        }
     }
+
+    Ok((rest, StringLiteral { span, value }))
 }
 
 // stolen from https://github.com/Geal/nom/blob/8e09f0c3029d32421b5b69fb798cef6855d0c8df/examples/string.rs#L36-L64
@@ -98,7 +174,7 @@ impl<'a> Parse<'a> for InlineExpression<'a> {
 }
 
 // Matches \ followed by an escape code
-fn parse_escape_codes(input: &str) -> nom::IResult<&str, char> {
+pub(crate) fn parse_escape_codes(input: &str) -> nom::IResult<&str, char> {
     use nom::character::complete::char;
 
     let tag_escape = char('\\');
@@ -212,4 +288,32 @@ mod tests {
             assert_eq!(rest, "");
         }
     }
+
+    fn value(input: &str) -> String {
+        let (rest, literal) = StringLiteral::parse(input).unwrap();
+        assert_eq!(rest, "");
+        literal.value
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_eq!(value(r#"r"C:\path\$x""#), r"C:\path\$x");
+        assert_eq!(value(r"r'\n$(x)'"), r"\n$(x)");
+    }
+
+    #[test]
+    fn text_blocks() {
+        let input = r#""""
+            fun main() =
+                println "hello"
+            """"#;
+        assert_eq!(value(input), "fun main() =\n    println \"hello\"");
+
+        // the closing quotes determine the indentation
+        let input = "\"\"\"\n    a\n\n      b\n  \"\"\"";
+        assert_eq!(value(input), "  a\n\n    b");
+
+        // escape codes are not interpreted
+        assert_eq!(value(r#""""one\ntwo $x""""#), r"one\ntwo $x");
+    }
 }
@@ -1,8 +1,8 @@
 use nom::{
     branch::alt,
-    combinator::{map, opt},
+    combinator::{cut, map, opt, peek, verify},
     multi::{many0, many1},
-    sequence::{delimited, pair, preceded},
+    sequence::{delimited, pair, preceded, terminated},
 };
 
 use super::*;
@@ -31,22 +31,59 @@ impl<'a> Parse<'a> for SubfieldGuard<'a> {
     }
 }
 
+/// [a, b, rest..]
+/// [first, .., last]
+/// [..init, last]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ArrayGuard<'a> {
     pub span: &'a str,
+    /// guards before the rest pattern
     pub subguards: Vec<Guard<'a>>,
-    pub tail: Option<Identifier<'a>>,
+    pub rest: Option<ArrayRest<'a>>,
+    /// guards after the rest pattern
+    pub trailing: Vec<Guard<'a>>,
 }
 impl<'a> Parse<'a> for ArrayGuard<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, _) = keywords::ParenClose::parse(input)?;
-        let (rest, subguards) = joined_by0(Guard::parse_ws, keywords::Comma::parse_ws)(rest)?;
-        let (rest, tail) = opt(delimited(
-            keywords::Comma::parse_ws,
-            Identifier::parse_ws,
-            keywords::Spread::parse_ws,
-        ))(rest)?;
-        let (rest, _) = opt(keywords::Comma::parse_ws)(rest)?;
+        use keywords::{BracketClose, BracketOpen, Comma};
+
+        enum Element<'a> {
+            Guard(Guard<'a>),
+            Rest(ArrayRest<'a>),
+        }
+
+        let element = |i| {
+            alt((
+                map(ArrayRest::parse_ws, Element::Rest),
+                map(Guard::parse_ws, Element::Guard),
+            ))(i)
+        };
+
+        // [
+        let (rest, _) = BracketOpen::parse(input)?;
+        // a, b, rest..,
+        let (rest, elements) = joined_by0(element, Comma::parse_ws)(rest)?;
+        // ]
+        let (rest, _) = BracketClose::parse_ws(rest)?;
+
+        let mut subguards = Vec::new();
+        let mut array_rest = None;
+        let mut trailing = Vec::new();
+
+        for element in elements {
+            match element {
+                Element::Guard(guard) if array_rest.is_none() => subguards.push(guard),
+                Element::Guard(guard) => trailing.push(guard),
+                Element::Rest(r) if array_rest.is_none() => array_rest = Some(r),
+                // There may only be one rest pattern
+                Element::Rest(r) => {
+                    return Err(nom::Err::Failure(nom::error::Error::new(
+                        r.span,
+                        nom::error::ErrorKind::Verify,
+                    )));
+                }
+            }
+        }
 
         let span = unsafe { from_to(input, rest) };
 
@@ -55,12 +92,49 @@ impl<'a> Parse<'a> for ArrayGuard<'a> {
             ArrayGuard {
                 span,
                 subguards,
-                tail,
+                rest: array_rest,
+                trailing,
             },
         ))
     }
 }
 
+/// Matches any number of elements in an array guard.
+/// May be bound to a variable, e.g. `rest..` or `..init`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArrayRest<'a> {
+    pub span: &'a str,
+    pub binding: Option<Identifier<'a>>,
+}
+impl<'a> Parse<'a> for ArrayRest<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        use keywords::{BracketClose, Comma, Spread};
+
+        let (rest, binding) = alt((
+            // ..init
+            map(preceded(Spread::parse, Identifier::parse_ws), Some),
+            // rest..
+            map(terminated(Identifier::parse, Spread::parse_ws), Some),
+            // ..
+            // must not be confused with a range guard like ..5
+            map(
+                terminated(
+                    Spread::parse,
+                    peek(alt((
+                        map(Comma::parse_ws, drop),
+                        map(BracketClose::parse_ws, drop),
+                    ))),
+                ),
+                |_| None,
+            ),
+        ))(input)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, ArrayRest { span, binding }))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ObjectGuard<'a> {
     pub span: &'a str,
@@ -91,10 +165,10 @@ pub struct TupleGuard<'a> {
 }
 impl<'a> Parse<'a> for TupleGuard<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        use keywords::{ParenClose, ParenOpen};
+        use keywords::{Comma, ParenClose, ParenOpen};
         let (rest, values) = delimited(
             ParenOpen::parse,
-            many0(Guard::parse_ws),
+            joined_by0(Guard::parse_ws, Comma::parse_ws),
             ParenClose::parse_ws,
         )(input)?;
 
@@ -104,58 +178,276 @@ impl<'a> Parse<'a> for TupleGuard<'a> {
     }
 }
 
+/// Patterns used in `when` branches, as well as on the left side of `let` and `for`.
+/// The latter only accept irrefutable guards, see `Guard::is_irrefutable`.
+///
+/// A single identifier always binds a variable, e.g. `x` or `None`.
+/// Whether it refers to an existing constructor is not known while parsing.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Guard<'a> {
     Literal(Literal<'a>),
+    NegativeLiteral(NegativeLiteral<'a>),
+    RangeGuard(RangeGuard<'a>),
     ObjectGuard(ObjectGuard<'a>),
     ArrayGuard(ArrayGuard<'a>),
     TupleGuard(TupleGuard<'a>),
     VariableBinding(Identifier<'a>),
+    BindingGuard(BindingGuard<'a>),
+    OrGuard(OrGuard<'a>),
 }
-impl<'a> Parse<'a> for Guard<'a> {
-    fn parse(input: &'a str) -> Res<'a, Self> {
+
+impl<'a> Guard<'a> {
+    /// Parses a single guard, that is not made up of alternatives.
+    fn parse_single(input: &'a str) -> Res<'a, Self> {
         alt((
+            map(RangeGuard::parse, Guard::RangeGuard),
+            map(NegativeLiteral::parse, Guard::NegativeLiteral),
             map(Literal::parse, Guard::Literal),
-            map(ObjectGuard::parse, Guard::ObjectGuard),
+            map(BindingGuard::parse, Guard::BindingGuard),
+            map(
+                verify(ObjectGuard::parse, |o| {
+                    o.struct_identifier.value.len() > 1 || !o.fields.is_empty()
+                }),
+                Guard::ObjectGuard,
+            ),
             map(ArrayGuard::parse, Guard::ArrayGuard),
             map(TupleGuard::parse, Guard::TupleGuard),
             map(Identifier::parse, Guard::VariableBinding),
         ))(input)
     }
+
+    fn parse_single_ws(input: &'a str) -> Res<'a, Self> {
+        let (rest, _comment) = crate::comment::parse_comment(input)?;
+        Self::parse_single(rest)
+    }
+
+    /// Parses a guard, that matches every value of the right shape.
+    /// Only those may be used in `let` and `for`, e.g. `(a, b)`, but not `7` or `a | b`.
+    pub(crate) fn parse_irrefutable_ws(input: &'a str) -> Res<'a, Self> {
+        verify(Guard::parse_ws, Guard::is_irrefutable)(input)
+    }
+
+    /// Whether the guard matches every value of the right shape.
+    ///
+    /// Arrays are only irrefutable with a rest pattern, e.g. `[first, rest..]`.
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Guard::VariableBinding(_) => true,
+            Guard::TupleGuard(t) => t.values.iter().all(Guard::is_irrefutable),
+            Guard::ObjectGuard(o) => o.fields.iter().all(|(_, field)| match field {
+                SubfieldGuard::VariableBinding(_) => true,
+                SubfieldGuard::Paren(guard) => guard.is_irrefutable(),
+                SubfieldGuard::Literal(_) => false,
+            }),
+            Guard::ArrayGuard(a) => {
+                a.rest.is_some()
+                    && a.subguards.iter().all(Guard::is_irrefutable)
+                    && a.trailing.iter().all(Guard::is_irrefutable)
+            }
+            Guard::BindingGuard(b) => b.guard.is_irrefutable(),
+            Guard::Literal(_)
+            | Guard::NegativeLiteral(_)
+            | Guard::RangeGuard(_)
+            | Guard::OrGuard(_) => false,
+        }
+    }
+}
+
+impl<'a> Parse<'a> for Guard<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, first) = Guard::parse_single(input)?;
+        let (rest, others) =
+            many0(preceded(keywords::Abs::parse_ws, Guard::parse_single_ws))(rest)?;
+
+        if others.is_empty() {
+            return Ok((rest, first));
+        }
+
+        let span = unsafe { from_to(input, rest) };
+        let alternatives = std::iter::once(first).chain(others).collect();
+
+        Ok((rest, Guard::OrGuard(OrGuard { span, alternatives })))
+    }
+}
+
+/// Numeric literal with a leading minus, e.g. `-1`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NegativeLiteral<'a> {
+    pub span: &'a str,
+    pub literal: Literal<'a>,
+}
+impl<'a> Parse<'a> for NegativeLiteral<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, _) = keywords::Minus::parse(input)?;
+        let (rest, literal) = alt((
+            map(
+                verify(literal::Float::parse, |f| !f.span.starts_with('-')),
+                Literal::Float,
+            ),
+            map(literal::Int::parse, Literal::Int),
+        ))(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, NegativeLiteral { span, literal }))
+    }
+}
+
+/// Range of literals, e.g. `0..=9`, `'a'..'z'` or `..-1`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RangeGuard<'a> {
+    pub span: &'a str,
+    pub start: Option<Box<Guard<'a>>>,
+    pub end: Option<Box<Guard<'a>>>,
+    /// true, if the range is written as `a..=b`
+    pub inclusive: bool,
+}
+impl<'a> Parse<'a> for RangeGuard<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        use crate::ast::expr::full::range_operator;
+
+        fn bound(input: &str) -> Res<'_, Box<Guard<'_>>> {
+            map(
+                alt((
+                    map(NegativeLiteral::parse, Guard::NegativeLiteral),
+                    map(Literal::parse, Guard::Literal),
+                )),
+                Box::new,
+            )(input)
+        }
+
+        let (rest, start) = opt(bound)(input)?;
+        let (rest, inclusive) = ws(range_operator)(rest)?;
+        let (rest, end) = opt(ws(bound))(rest)?;
+
+        // .. on its own is not a range
+        // and ..= needs an upper bound
+        if (start.is_none() && end.is_none()) || (inclusive && end.is_none()) {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((
+            rest,
+            RangeGuard {
+                span,
+                start,
+                end,
+                inclusive,
+            },
+        ))
+    }
+}
+
+/// Binds the value matched by a guard to a variable, e.g. `p @ Point x 0`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BindingGuard<'a> {
+    pub span: &'a str,
+    pub name: Identifier<'a>,
+    pub guard: Box<Guard<'a>>,
+}
+impl<'a> Parse<'a> for BindingGuard<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, name) = Identifier::parse(input)?;
+        let (rest, _) = keywords::At::parse_ws(rest)?;
+        let (rest, guard) = map(cut(Guard::parse_single_ws), Box::new)(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, BindingGuard { span, name, guard }))
+    }
 }
 
+/// Alternatives, of which any may match, e.g. `Red | Yellow`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrGuard<'a> {
+    pub span: &'a str,
+    pub alternatives: Vec<Guard<'a>>,
+}
+
+/// is Point x px where isPositive px => px
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Branch<'a> {
     pub span: &'a str,
     pub guard: Guard<'a>,
+    /// Additional boolean condition, that needs to hold for the branch to be taken
+    pub guard_condition: Option<FullExpression<'a>>,
     pub then: FullExpression<'a>,
 }
 impl<'a> Parse<'a> for Branch<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         let (rest, _) = keywords::Is::parse(input)?;
         let (rest, guard) = Guard::parse_ws(rest)?;
+        let (rest, guard_condition) = opt(preceded(
+            keywords::Where::parse_ws,
+            cut(FullExpression::parse_ws),
+        ))(rest)?;
         let (rest, _) = keywords::FatArrow::parse_ws(rest)?;
         let (rest, then) = FullExpression::parse_ws(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
-        Ok((rest, Branch { span, guard, then }))
+        Ok((
+            rest,
+            Branch {
+                span,
+                guard,
+                guard_condition,
+                then,
+            },
+        ))
     }
 }
 
+/// when x
+/// is Some value v => v
+/// is None => 0
+///
+/// when x > 0 then x else 0
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct When<'a> {
-    pub span: &'a str,
-    pub condition: FullExpression<'a>,
-    pub branches: Vec<Branch<'a>>,
-    pub else_clause: Option<FullExpression<'a>>,
+pub enum When<'a> {
+    Is(WhenIs<'a>),
+    Then(WhenThen<'a>),
+}
+
+impl<'a> When<'a> {
+    pub fn span(&self) -> &'a str {
+        match self {
+            When::Is(w) => w.span,
+            When::Then(w) => w.span,
+        }
+    }
 }
 
 impl<'a> Parse<'a> for When<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         let (rest, _) = keywords::When::parse(input)?;
         let (rest, condition) = FullExpression::parse_ws(rest)?;
-        let (rest, branches) = many1(Branch::parse_ws)(rest)?;
+
+        // when <condition> then <expr> else <expr>
+        if let Ok((rest, _)) = keywords::Then::parse_ws(rest) {
+            let (rest, then) = cut(FullExpression::parse_ws)(rest)?;
+            let (rest, else_clause) =
+                cut(preceded(keywords::Else::parse_ws, FullExpression::parse_ws))(rest)?;
+
+            let span = unsafe { from_to(input, rest) };
+
+            return Ok((
+                rest,
+                When::Then(WhenThen {
+                    span,
+                    condition,
+                    then,
+                    else_clause,
+                }),
+            ));
+        }
+
+        let (rest, branches) = cut(many1(Branch::parse_ws))(rest)?;
         let (rest, else_clause) =
             opt(preceded(keywords::Else::parse_ws, FullExpression::parse_ws))(rest)?;
 
@@ -163,12 +455,301 @@ impl<'a> Parse<'a> for When<'a> {
 
         Ok((
             rest,
-            When {
+            When::Is(WhenIs {
                 span,
                 condition,
                 branches,
                 else_clause,
-            },
+            }),
         ))
     }
 }
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WhenIs<'a> {
+    pub span: &'a str,
+    pub condition: FullExpression<'a>,
+    pub branches: Vec<Branch<'a>>,
+    pub else_clause: Option<FullExpression<'a>>,
+}
+
+/// Shorthand for branching on a boolean condition
+/// when x > 0 then x else 0
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WhenThen<'a> {
+    pub span: &'a str,
+    pub condition: FullExpression<'a>,
+    pub then: FullExpression<'a>,
+    pub else_clause: FullExpression<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_is() {
+        let input = "when x is 0 => a is n => b else c";
+        let (rest, when) = When::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let When::Is(when) = when else {
+            panic!("expected when-is expression");
+        };
+        assert_eq!(when.branches.len(), 2);
+        assert!(when.else_clause.is_some());
+    }
+
+    #[test]
+    fn guards() {
+        let input = [
+            ("x", "VariableBinding"),
+            ("None", "VariableBinding"),
+            ("Color.Red", "ObjectGuard"),
+            ("Point x 0 y py", "ObjectGuard"),
+            ("(a, b)", "TupleGuard"),
+            ("(a, (b, c),)", "TupleGuard"),
+            ("[]", "ArrayGuard"),
+            ("[a, b]", "ArrayGuard"),
+            ("[a, rest..]", "ArrayGuard"),
+            ("[rest..]", "ArrayGuard"),
+            ("7", "Literal"),
+            ("-7", "NegativeLiteral"),
+            ("-7.5", "NegativeLiteral"),
+            ("-1.5..-0.5", "RangeGuard"),
+            ("0..=9", "RangeGuard"),
+            ("-10..-1", "RangeGuard"),
+            ("..0", "RangeGuard"),
+            ("100..", "RangeGuard"),
+            ("p @ Point x 0", "BindingGuard"),
+            ("Red | Yellow", "OrGuard"),
+            ("0 | 1..=9 | n", "OrGuard"),
+        ];
+
+        for (i, kind) in input {
+            let (rest, guard) = Guard::parse(i).unwrap();
+            assert_eq!(rest, "", "expect to parse '{i}' without rest");
+
+            let got = match guard {
+                Guard::Literal(_) => "Literal",
+                Guard::ObjectGuard(_) => "ObjectGuard",
+                Guard::ArrayGuard(_) => "ArrayGuard",
+                Guard::TupleGuard(_) => "TupleGuard",
+                Guard::VariableBinding(_) => "VariableBinding",
+                Guard::NegativeLiteral(_) => "NegativeLiteral",
+                Guard::RangeGuard(_) => "RangeGuard",
+                Guard::BindingGuard(_) => "BindingGuard",
+                Guard::OrGuard(_) => "OrGuard",
+            };
+            assert_eq!(got, kind, "guard kind of '{i}'");
+        }
+    }
+
+    #[test]
+    fn or_guard() {
+        let (rest, guard) = Guard::parse("p @ Red | Yellow").unwrap();
+        assert_eq!(rest, "");
+
+        let Guard::OrGuard(OrGuard { alternatives, .. }) = guard else {
+            panic!("expected alternatives");
+        };
+        assert!(matches!(alternatives[0], Guard::BindingGuard(_)));
+        assert_eq!(
+            alternatives[1],
+            Guard::VariableBinding(Identifier::from_str("Yellow"))
+        );
+    }
+
+    #[test]
+    fn range_guard_needs_bound() {
+        assert!(RangeGuard::parse("..").is_err());
+        assert!(RangeGuard::parse("1..=").is_err());
+    }
+
+    #[test]
+    fn when_with_new_guards() {
+        let input = "when n is 0 => zero is -9..=-1 | 1..=9 => digit is x @ 10.. => big x";
+        let (rest, when) = When::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let When::Is(when) = when else {
+            panic!("expected when-is expression");
+        };
+        assert_eq!(when.branches.len(), 3);
+    }
+
+    #[test]
+    fn array_guard_rest() {
+        let (rest, guard) = ArrayGuard::parse("[first, rest..]").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            guard.subguards,
+            vec![Guard::VariableBinding(Identifier::from_str("first"))]
+        );
+        assert_eq!(
+            guard.rest,
+            Some(ArrayRest {
+                span: "rest..",
+                binding: Some(Identifier::from_str("rest")),
+            })
+        );
+        assert_eq!(guard.trailing, vec![]);
+
+        let (rest, guard) = ArrayGuard::parse("[first, .., last]").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(guard.subguards.len(), 1);
+        assert_eq!(
+            guard.rest,
+            Some(ArrayRest {
+                span: "..",
+                binding: None,
+            })
+        );
+        assert_eq!(guard.trailing.len(), 1);
+
+        let (rest, guard) = ArrayGuard::parse("[..init, last]").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(guard.subguards, vec![]);
+        assert_eq!(
+            guard.rest,
+            Some(ArrayRest {
+                span: "..init",
+                binding: Some(Identifier::from_str("init")),
+            })
+        );
+        assert_eq!(
+            guard.trailing,
+            vec![Guard::VariableBinding(Identifier::from_str("last"))]
+        );
+
+        let (rest, guard) = ArrayGuard::parse("[..5, ..]").unwrap();
+        assert_eq!(rest, "");
+        assert!(matches!(guard.subguards[0], Guard::RangeGuard(_)));
+        assert!(guard.rest.is_some());
+    }
+
+    #[test]
+    fn array_guard_single_rest() {
+        let result = ArrayGuard::parse("[a.., ..b]");
+        assert!(matches!(result, Err(nom::Err::Failure(_))));
+    }
+
+    #[test]
+    fn when_with_array_guards() {
+        let input = "when xs
+            is [] => empty
+            is [x] => single x
+            is [a, b, rest..] => many a b rest
+            is [first, .., last] => ends first last
+            is [..init, last] => init
+            is [0, y,] => zero";
+        let (rest, when) = When::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let When::Is(when) = when else {
+            panic!("expected when-is expression");
+        };
+        assert_eq!(when.branches.len(), 6);
+        assert!(when
+            .branches
+            .iter()
+            .all(|b| matches!(b.guard, Guard::ArrayGuard(_))));
+    }
+
+    #[test]
+    fn branch_guard_condition() {
+        let input = "is Point x px where isPositive px => px";
+        let (rest, branch) = Branch::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            branch.guard_condition,
+            Some(FullExpression::from_str("isPositive px"))
+        );
+
+        let input = "is Some value x where x > 10 => x";
+        let (rest, branch) = Branch::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            branch.guard_condition,
+            Some(FullExpression::from_str("x > 10"))
+        );
+
+        let (rest, branch) = Branch::parse("is n => n").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(branch.guard_condition, None);
+    }
+
+    #[test]
+    fn when_with_guard_conditions() {
+        let input = "when x is n where even n => a is n where odd n => b else c";
+        let (rest, when) = When::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let When::Is(when) = when else {
+            panic!("expected when-is expression");
+        };
+        assert!(when.branches.iter().all(|b| b.guard_condition.is_some()));
+    }
+
+    #[test]
+    fn when_then() {
+        let input = "when done then a else b";
+        let (rest, when) = When::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            when,
+            When::Then(WhenThen {
+                span: input,
+                condition: FullExpression::from_str("done"),
+                then: FullExpression::from_str("a"),
+                else_clause: FullExpression::from_str("b"),
+            })
+        );
+    }
+
+    #[test]
+    fn doc_examples() {
+        for input in [
+            "when x\nis Some value v => v\nis None => 0",
+            "when x > 0 then x else 0",
+        ] {
+            let (rest, _) = When::parse(input).unwrap();
+            assert_eq!(rest, "", "{}", input);
+        }
+    }
+
+    #[test]
+    fn when_then_needs_else() {
+        assert!(When::parse("when done then a").is_err());
+    }
+
+    #[test]
+    fn when_needs_branches() {
+        let result = When::parse("when x else b");
+        assert!(matches!(result, Err(nom::Err::Failure(_))));
+    }
+
+    #[test]
+    fn when_then_with_branches() {
+        // branches are not part of the sugar
+        let (rest, when) = When::parse("when x then a else b is 1 => c").unwrap();
+        assert!(matches!(when, When::Then(_)));
+        assert_eq!(rest, " is 1 => c");
+    }
+
+    #[test]
+    fn nested_when_then() {
+        let input = "when x is 0 => when c then a else b is 1 => d";
+        let (rest, when) = When::parse(input).unwrap();
+        assert_eq!(rest, "");
+        let When::Is(when) = when else {
+            panic!("expected when-is expression");
+        };
+        assert_eq!(when.branches.len(), 2);
+        assert_eq!(
+            when.branches[0].then,
+            FullExpression::from_str("when c then a else b")
+        );
+        assert_eq!(when.branches[1].then, FullExpression::from_str("d"));
+    }
+}
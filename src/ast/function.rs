@@ -8,27 +8,104 @@ use crate::{ast::expr::FullExpression, parse::Res};
 
 use crate::ast::identifier::Identifier;
 
-use super::{keywords, Type};
+use super::{
+    attributes, keywords, structs::where_clause, Attribute, GenericSymbols, Type, Visibility,
+};
 
 /// Represents a function declaration.
 /// e.g.
 /// export fun fib(n: Int) = { if (n == 0) 0; if (n == 1) 1; fib (n-1) + fib (n-2)}
 /// fun string(person) = person.name
+/// fun sort(xs: List t) -> List t where t: Ord = ...
+/// generic (t, e) fun unwrap(r: Result t e) -> t = ...
+/// async fun download(url: String) -> Bytes = await fetch url
+/// fun naturals() yields Int = { let mut n = 0; loop { yield n; n += 1 } }
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Function<'a> {
     pub span: &'a str,
-    pub exported: bool,
+    pub attributes: Vec<Attribute<'a>>,
+    pub visibility: Visibility,
+    pub is_async: bool,
+    pub generic_symbols: Option<GenericSymbols<'a>>,
     pub name: Identifier<'a>,
-    pub args: Vec<(Identifier<'a>, Option<Type<'a>>)>,
+    pub args: Vec<Param<'a>>,
     pub ret: Option<Type<'a>>,
+    /// Type of the values produced by `yield`
+    pub yields: Option<Type<'a>>,
+    pub constraints: Vec<(Identifier<'a>, Vec<Type<'a>>)>,
     pub body: FullExpression<'a>,
 }
 
 impl<'a> Parse<'a> for Function<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
+        // @inline
+        let (rest, attributes) = attributes(input)?;
+
         // export
-        let (rest, exported) = opt(keywords::Export::parse)(input)?;
-        let exported = exported.is_some();
+        let (rest, visibility) = Visibility::parse(rest)?;
+
+        // generic t fun fib(n: Int) -> Int
+        let (rest, signature) = Signature::parse_ws(rest)?;
+
+        let (rest, _) = keywords::Assign::parse_ws(rest)?;
+
+        let (rest, body) = FullExpression::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        let Signature {
+            is_async,
+            generic_symbols,
+            name,
+            args,
+            ret,
+            yields,
+            constraints,
+        } = signature;
+
+        Ok((
+            rest,
+            Function {
+                span,
+                attributes,
+                visibility,
+                is_async,
+                generic_symbols,
+                name,
+                args,
+                ret,
+                yields,
+                constraints,
+                body,
+            },
+        ))
+    }
+}
+
+/// Everything of a function declaration, that comes before its body.
+/// e.g.
+/// fun sort(xs: List t) -> List t where t: Ord
+pub(crate) struct Signature<'a> {
+    pub is_async: bool,
+    pub generic_symbols: Option<GenericSymbols<'a>>,
+    pub name: Identifier<'a>,
+    pub args: Vec<Param<'a>>,
+    pub ret: Option<Type<'a>>,
+    pub yields: Option<Type<'a>>,
+    pub constraints: Vec<(Identifier<'a>, Vec<Type<'a>>)>,
+}
+
+impl<'a> Parse<'a> for Signature<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        // generic (t, e)
+        let (rest, generic_symbols) = opt(preceded(
+            keywords::Generic::parse,
+            cut(GenericSymbols::parse_ws),
+        ))(input)?;
+
+        // async
+        let (rest, is_async) = opt(keywords::Async::parse_ws)(rest)?;
+        let is_async = is_async.is_some();
 
         // fun
         let (rest, _) = keywords::Function::parse_ws(rest)?;
@@ -38,43 +115,97 @@ impl<'a> Parse<'a> for Function<'a> {
 
         let (rest, _) = keywords::ParenOpen::parse_ws(rest)?;
 
-        let args = |input| {
-            let (rest, ident) = Identifier::parse_ws(input)?;
-            let (rest, ty) = opt(preceded(
-                keywords::TypeHint::parse_ws,
-                // After the type hint there MUST come an identifier.
-                cut(Type::parse_ws),
-            ))(rest)?;
-
-            Ok((rest, (ident, ty)))
-        };
-
-        let (rest, args) = joined_by0(args, keywords::Comma::parse_ws)(rest)?;
+        let (rest, args) = params(rest)?;
 
         let (rest, _) = keywords::ParenClose::parse_ws(rest)?;
 
         let (rest, ret) = opt(preceded(keywords::ThinArrow::parse_ws, cut(Type::parse_ws)))(rest)?;
 
-        let (rest, _) = keywords::Assign::parse_ws(rest)?;
+        // yields Int
+        let (rest, yields) = opt(preceded(keywords::Yields::parse_ws, cut(Type::parse_ws)))(rest)?;
 
-        let (rest, body) = FullExpression::parse_ws(rest)?;
+        // where t: Ord
+        let (rest, constraints) = where_clause(rest)?;
+
+        Ok((
+            rest,
+            Signature {
+                is_async,
+                generic_symbols,
+                name,
+                args,
+                ret,
+                yields,
+                constraints,
+            },
+        ))
+    }
+}
+
+/// Declared parameter of a function or closure
+/// e.g.
+/// n
+/// n: Int
+/// port: Int = 80
+/// nums: Int..
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Param<'a> {
+    pub span: &'a str,
+    pub name: Identifier<'a>,
+    pub ty: Option<Type<'a>>,
+    /// Collects all remaining arguments, e.g. `nums: Int..`
+    pub variadic: bool,
+    /// Value used, if the argument is omitted at the call site
+    pub default: Option<FullExpression<'a>>,
+}
+
+impl<'a> Parse<'a> for Param<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, name) = Identifier::parse(input)?;
+        let (rest, ty) = opt(preceded(
+            keywords::TypeHint::parse_ws,
+            // After the type hint there MUST come a type.
+            cut(Type::parse_ws),
+        ))(rest)?;
+        let (rest, variadic) = opt(keywords::Spread::parse_ws)(rest)?;
+        let variadic = variadic.is_some();
+        let (rest, default) = opt(preceded(
+            keywords::Assign::parse_ws,
+            cut(FullExpression::parse_ws),
+        ))(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
         Ok((
             rest,
-            Function {
+            Param {
                 span,
-                exported,
                 name,
-                args,
-                ret,
-                body,
+                ty,
+                variadic,
+                default,
             },
         ))
     }
 }
 
+/// Parses the comma separated parameters of a function or closure.
+/// Only the last one may be variadic and it takes no default, e.g. `(sep: String, parts: String..)`
+pub(crate) fn params(input: &str) -> Res<'_, Vec<Param<'_>>> {
+    let (rest, params) = joined_by0(Param::parse_ws, keywords::Comma::parse_ws)(input)?;
+
+    let misplaced = params.iter().rev().skip(1).find(|p| p.variadic);
+    let defaulted = params.iter().find(|p| p.variadic && p.default.is_some());
+    if let Some(param) = misplaced.or(defaulted) {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            param.span,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+
+    Ok((rest, params))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,10 +216,15 @@ mod tests {
         let expected = Function {
             body: FullExpression::from_str("println 'hello'"),
             span: input,
+            attributes: Vec::new(),
             ret: None,
-            exported: false,
+            yields: None,
+            visibility: Visibility::Private,
+            is_async: false,
+            generic_symbols: None,
             name: Identifier::from_str("main"),
             args: Vec::new(),
+            constraints: Vec::new(),
         };
 
         let (rest, got) = Function::parse(input).unwrap();
@@ -97,4 +233,131 @@ mod tests {
 
         assert_eq!(got, expected);
     }
+
+    #[test]
+    fn default_params() {
+        let input = "fun connect(host: String, port: Int = 80, secure = false) = host";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(
+            got.args,
+            vec![
+                Param {
+                    span: "host: String",
+                    name: Identifier::from_str("host"),
+                    ty: Some(Type::from_str("String")),
+                    variadic: false,
+                    default: None,
+                },
+                Param {
+                    span: "port: Int = 80",
+                    name: Identifier::from_str("port"),
+                    ty: Some(Type::from_str("Int")),
+                    variadic: false,
+                    default: Some(FullExpression::from_str("80")),
+                },
+                Param {
+                    span: "secure = false",
+                    name: Identifier::from_str("secure"),
+                    ty: None,
+                    variadic: false,
+                    default: Some(FullExpression::from_str("false")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn variadic_params() {
+        let input = "fun sum(first: Int, nums: Int..) = first";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert!(!got.args[0].variadic);
+        assert_eq!(
+            got.args[1],
+            Param {
+                span: "nums: Int..",
+                name: Identifier::from_str("nums"),
+                ty: Some(Type::from_str("Int")),
+                variadic: true,
+                default: None,
+            }
+        );
+    }
+
+    #[test]
+    fn variadic_params_come_last() {
+        for input in [
+            "fun f(a: Int.., b: Int) = a",
+            "fun f(a.., b..) = a",
+            "fun f(a: Int.. = 1) = a",
+        ] {
+            assert!(
+                matches!(Function::parse(input), Err(nom::Err::Failure(_))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn async_fn() {
+        let input = "export async fun download(url: String) -> Bytes = await fetch url";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(got.visibility, Visibility::Export);
+        assert!(got.is_async);
+        assert_eq!(got.body, FullExpression::from_str("await fetch url"));
+    }
+
+    #[test]
+    fn generator_fn() {
+        let input = "fun naturals() yields Int = { let mut n = 0; loop { yield n; n += 1 } }";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(got.ret, None);
+        assert_eq!(got.yields, Some(Type::from_str("Int")));
+    }
+
+    #[test]
+    fn generic_fn() {
+        let input = "generic (t, e, u) fun map(r: Result t e, f: fun(t) => u) -> Result u e = r";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(
+            got.generic_symbols,
+            Some(GenericSymbols::from_str("(t, e, u)"))
+        );
+
+        let input = "export generic t fun identity(x: t) -> t = x";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(got.visibility, Visibility::Export);
+        assert_eq!(got.generic_symbols, Some(GenericSymbols::from_str("t")));
+    }
+
+    #[test]
+    fn constrained_fn() {
+        let input = "fun sort(xs: List t) -> List t where t: Ord = xs";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(
+            got.constraints,
+            vec![(Identifier::from_str("t"), vec![Type::from_str("Ord")])]
+        );
+
+        let input = "fun show(x: t, y: u) where t: Show, u: Show + Eq = x";
+        let (rest, got) = Function::parse(input).unwrap();
+
+        assert_eq!(rest, "", "expect to consume entire input");
+        assert_eq!(got.constraints.len(), 2);
+        assert_eq!(got.constraints[1].1.len(), 2);
+    }
 }
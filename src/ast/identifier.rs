@@ -19,7 +19,7 @@ impl<'a> Parse<'a> for IdentifierPath<'a> {
         let span = unsafe { from_to(input, rest) };
 
         // make an array of the first and the following paths
-        let value = std::iter::once(first).chain(path.into_iter()).collect();
+        let value = std::iter::once(first).chain(path).collect();
 
         Ok((rest, IdentifierPath { span, value }))
     }
@@ -85,6 +85,21 @@ impl<'a> Parse<'a> for Identifier<'a> {
         let secondpart = take_while(|c| isalpha(c) || isnumber(c) || c == '_');
         let (rest, value) = verify(recognize(pair(firstpart, secondpart)), verify_ident)(input)?;
 
+        // c'a' is a char, r'...' and r"..." are raw strings.
+        // Those are parsed as literals, which come before identifiers in `Value`.
+        // `c"abc"` remains a call of `c`.
+        let literal_prefix = match value {
+            "c" => rest.starts_with('\''),
+            "r" => rest.starts_with(['\'', '"']),
+            _ => false,
+        };
+        if literal_prefix {
+            return Err(nom::Err::Error(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Verify,
+            )));
+        }
+
         Ok((rest, Identifier { value, span: value }))
     }
 }
@@ -92,19 +107,24 @@ impl<'a> Parse<'a> for Identifier<'a> {
 pub fn is_keyword(word: &str) -> bool {
     [
         "and",
+        "as",
         "break",
         "do",
         "else",
+        "export",
         "false",
         "for",
         "generic",
         "if",
+        "impl",
         "in",
+        "interface",
         "is",
         "let",
         "loop",
         "mut",
         "next",
+        "opaque",
         "or",
         "pub",
         "return",
@@ -114,13 +134,15 @@ pub fn is_keyword(word: &str) -> bool {
         "type",
         "use",
         "when",
+        "while",
+        "with",
         "async",
         "await",
         "fun",
         "where",
         "buildin_type",
         "yield",
-        "yields"
+        "yields",
     ]
     .contains(&word)
 }
@@ -135,6 +157,18 @@ mod tests {
         assert!(is_keyword("for"));
         assert!(is_keyword("use"));
         assert!(is_keyword("type"));
+        for word in [
+            "as",
+            "export",
+            "impl",
+            "interface",
+            "opaque",
+            "while",
+            "with",
+        ] {
+            assert!(is_keyword(word), "{}", word);
+            assert!(Identifier::parse(word).is_err(), "{}", word);
+        }
         assert!(!is_keyword("x"));
         assert!(!is_keyword("y"));
         assert!(!is_keyword("point"));
@@ -151,6 +185,17 @@ mod tests {
         assert_eq!(res.1.value, "hello");
     }
 
+    #[test]
+    fn literal_prefixes() {
+        assert!(Identifier::parse("c'a'").is_err());
+        assert!(Identifier::parse("r'raw'").is_err());
+        assert!(Identifier::parse("r\"raw\"").is_err());
+
+        assert_eq!(Identifier::parse("c\"abc\"").unwrap().0, "\"abc\"");
+        assert_eq!(Identifier::parse("c 'a'").unwrap().0, " 'a'");
+        assert_eq!(Identifier::parse("cr'a'").unwrap().0, "'a'");
+    }
+
     #[test]
     fn fullidents() {
         let span = "hello.world 7";
@@ -5,14 +5,17 @@ use crate::{ast::identifier::Identifier, ast::*, parse::*, util::*};
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Import<'a> {
     pub span: &'a str,
+    /// `export use std.io..` re-exports the imported items
+    pub visibility: Visibility,
     /// If true, find the import in some library, otherwise imported from the projects root file
     pub is_lib: bool,
     /// Path pointing to where to find the code
     /// e.g. collection.array
     pub path: Vec<Identifier<'a>>,
     /// Items that are supposed to be imported from the path
-    /// e.g.    (Array, sort, binarySearch)
-    ///         *
+    /// e.g.    .(Array, sort, binarySearch)
+    ///         ..
+    ///         as alias
     pub items: Selection<'a>,
 }
 
@@ -20,7 +23,8 @@ impl<'a> Parse<'a> for Import<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         use nom::combinator::opt;
 
-        let (rest, _) = keywords::Use::parse(input)?;
+        let (rest, visibility) = Visibility::parse(input)?;
+        let (rest, _) = keywords::Use::parse(rest)?;
 
         let (rest, is_lib) = opt(keywords::At::parse_ws)(rest)?;
         let is_lib = is_lib.is_some();
@@ -35,6 +39,7 @@ impl<'a> Parse<'a> for Import<'a> {
             rest,
             Import {
                 span,
+                visibility,
                 is_lib,
                 path,
                 items,
@@ -46,9 +51,13 @@ impl<'a> Parse<'a> for Import<'a> {
 /// Selection of imported items
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub enum Selection<'a> {
+    /// std.io..
     All,
-    This,
-    Items(Vec<Identifier<'a>>),
+    /// std.io
+    /// std.io as sysio
+    This(Option<Identifier<'a>>),
+    /// std.(io as sysio, fs, collections..)
+    Items(Vec<SelectionItem<'a>>),
 }
 
 impl<'a> Parse<'a> for Selection<'a> {
@@ -59,13 +68,39 @@ impl<'a> Parse<'a> for Selection<'a> {
 
         if let Ok((rest, _)) = keywords::Dot::parse(input) {
             let (rest, _) = cut(keywords::ParenOpen::parse_ws)(rest)?;
-            let (rest, items) = joined_by0(Identifier::parse_ws, keywords::Comma::parse_ws)(rest)?;
+            let (rest, items) =
+                joined_by0(SelectionItem::parse_ws, keywords::Comma::parse_ws)(rest)?;
             let (rest, _) = cut(keywords::ParenClose::parse_ws)(rest)?;
 
             return Ok((rest, Selection::Items(items)));
         }
 
-        Ok((input, Selection::This))
+        if let Ok((rest, _)) = keywords::As::parse(input) {
+            let (rest, alias) = cut(Identifier::parse_ws)(rest)?;
+            return Ok((rest, Selection::This(Some(alias))));
+        }
+
+        Ok((input, Selection::This(None)))
+    }
+}
+
+/// Single entry of a selection, which may select further items itself.
+/// e.g. the `io.(read, write)` in `use std.(io.(read, write), fs)`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SelectionItem<'a> {
+    pub span: &'a str,
+    pub path: Vec<Identifier<'a>>,
+    pub items: Selection<'a>,
+}
+
+impl<'a> Parse<'a> for SelectionItem<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, path) = separated_list1(keywords::Dot::parse_ws, Identifier::parse_ws)(input)?;
+        let (rest, items) = Selection::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((rest, SelectionItem { span, path, items }))
     }
 }
 
@@ -92,6 +127,7 @@ mod tests {
             import,
             Import {
                 span: input.trim_end(),
+                visibility: Visibility::Private,
                 is_lib: true,
                 path: "std.collections.hashmap"
                     .split('.')
@@ -111,12 +147,17 @@ mod tests {
             import,
             Import {
                 span: input,
+                visibility: Visibility::Private,
                 is_lib: true,
                 path: "std"
                     .split('.')
                     .map(|value| Identifier { span: value, value })
                     .collect(),
-                items: Selection::Items(vec![Identifier::parse("io").unwrap().1]),
+                items: Selection::Items(vec![SelectionItem {
+                    span: "io",
+                    path: vec![Identifier::from_str("io")],
+                    items: Selection::This(None),
+                }]),
             }
         );
         assert_eq!(rest, "");
@@ -130,12 +171,13 @@ mod tests {
             import,
             Import {
                 span: input,
+                visibility: Visibility::Private,
                 is_lib: true,
                 path: "std.io"
                     .split('.')
                     .map(|value| Identifier { span: value, value })
                     .collect(),
-                items: Selection::This,
+                items: Selection::This(None),
             }
         );
         assert_eq!(rest, "");
@@ -154,6 +196,11 @@ mod tests {
             "use std..",
             "use std.io..",
             "use std.debug..",
+            "export use std.io..",
+            "pub use std.(io, fs)",
+            "use std.io as sysio",
+            "use std.(io as sysio, fs)",
+            "use std.(io.(read, write), collections..)",
         ];
 
         for i in input {
@@ -162,4 +209,43 @@ mod tests {
             assert_eq!(rest, "", "expect to parse '{i}' without rest");
         }
     }
+
+    #[test]
+    fn nested_imports() {
+        let input = "use std.(io.(read as r, write), collections.., fs as files)";
+        let (rest, import) = Import::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let Selection::Items(items) = import.items else {
+            panic!("expected a list of items");
+        };
+        assert_eq!(items.len(), 3);
+
+        assert_eq!(items[0].span, "io.(read as r, write)");
+        let Selection::Items(io) = &items[0].items else {
+            panic!("expected nested items");
+        };
+        assert_eq!(
+            io[0].items,
+            Selection::This(Some(Identifier::from_str("r")))
+        );
+        assert_eq!(io[1].items, Selection::This(None));
+
+        assert_eq!(items[1].items, Selection::All);
+        assert_eq!(items[2].span, "fs as files");
+        assert_eq!(
+            items[2].items,
+            Selection::This(Some(Identifier::from_str("files")))
+        );
+    }
+
+    #[test]
+    fn aliased_import() {
+        let (rest, import) = Import::parse("use std.io as sysio").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            import.items,
+            Selection::This(Some(Identifier::from_str("sysio")))
+        );
+    }
 }
@@ -0,0 +1,237 @@
+use nom::{combinator::opt, multi::many0, sequence::preceded};
+
+use crate::{ast::expr::FullExpression, parse::Res, util::from_to, Parse};
+
+use super::{
+    attributes,
+    function::{Function, Param, Signature},
+    identifier::{Identifier, IdentifierPath},
+    keywords,
+    structs::where_clause,
+    Attribute, GenericSymbols, Type, Visibility,
+};
+
+/// Declares shared behaviour of types.
+/// e.g.
+/// interface Show t {
+///     fun show(x: t) -> String
+///     fun debug(x: t) -> String = show x
+/// }
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Interface<'a> {
+    pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
+    pub visibility: Visibility,
+    pub name: Identifier<'a>,
+    pub generic_symbols: Option<GenericSymbols<'a>>,
+    pub constraints: Vec<(Identifier<'a>, Vec<Type<'a>>)>,
+    pub functions: Vec<InterfaceFunction<'a>>,
+}
+
+impl<'a> Parse<'a> for Interface<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, attributes) = attributes(input)?;
+        let (rest, visibility) = Visibility::parse(rest)?;
+        // interface
+        let (rest, _) = keywords::Interface::parse(rest)?;
+        // Show
+        let (rest, name) = Identifier::parse_ws(rest)?;
+        // t
+        let (rest, generic_symbols) = opt(GenericSymbols::parse_ws)(rest)?;
+        // where t: Eq
+        let (rest, constraints) = where_clause(rest)?;
+
+        // { fun show(x: t) -> String ... }
+        let (rest, _) = keywords::CurlyOpen::parse_ws(rest)?;
+        let (rest, functions) = many0(InterfaceFunction::parse_ws)(rest)?;
+        let (rest, _) = keywords::CurlyClose::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((
+            rest,
+            Interface {
+                span,
+                attributes,
+                visibility,
+                name,
+                generic_symbols,
+                constraints,
+                functions,
+            },
+        ))
+    }
+}
+
+/// Function signature inside of an interface,
+/// with an optional default implementation.
+/// e.g.
+/// fun show(x: t) -> String
+/// fun debug(x: t) -> String = show x
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterfaceFunction<'a> {
+    pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
+    pub is_async: bool,
+    pub generic_symbols: Option<GenericSymbols<'a>>,
+    pub name: Identifier<'a>,
+    pub args: Vec<Param<'a>>,
+    pub ret: Option<Type<'a>>,
+    pub yields: Option<Type<'a>>,
+    pub constraints: Vec<(Identifier<'a>, Vec<Type<'a>>)>,
+    pub default: Option<FullExpression<'a>>,
+}
+
+impl<'a> Parse<'a> for InterfaceFunction<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, attributes) = attributes(input)?;
+        let (rest, signature) = Signature::parse(rest)?;
+        let (rest, default) = opt(preceded(
+            keywords::Assign::parse_ws,
+            FullExpression::parse_ws,
+        ))(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        let Signature {
+            is_async,
+            generic_symbols,
+            name,
+            args,
+            ret,
+            yields,
+            constraints,
+        } = signature;
+
+        Ok((
+            rest,
+            InterfaceFunction {
+                span,
+                attributes,
+                is_async,
+                generic_symbols,
+                name,
+                args,
+                ret,
+                yields,
+                constraints,
+                default,
+            },
+        ))
+    }
+}
+
+/// Implements an interface for a type.
+/// e.g.
+/// impl Show Point {
+///     fun show(p: Point) -> String = "Point"
+/// }
+///
+/// impl Show List t where t: Show {
+///     fun show(xs: List t) -> String = join (xs /> map show) ", "
+/// }
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Impl<'a> {
+    pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
+    pub visibility: Visibility,
+    pub interface: IdentifierPath<'a>,
+    pub ty: Type<'a>,
+    pub constraints: Vec<(Identifier<'a>, Vec<Type<'a>>)>,
+    pub functions: Vec<Function<'a>>,
+}
+
+impl<'a> Parse<'a> for Impl<'a> {
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, attributes) = attributes(input)?;
+        let (rest, visibility) = Visibility::parse(rest)?;
+        // impl
+        let (rest, _) = keywords::Impl::parse(rest)?;
+        // Show
+        let (rest, interface) = IdentifierPath::parse_ws(rest)?;
+        // List t
+        let (rest, ty) = Type::parse_ws(rest)?;
+        // where t: Show
+        let (rest, constraints) = where_clause(rest)?;
+
+        // { fun show(xs: List t) -> String = ... }
+        let (rest, _) = keywords::CurlyOpen::parse_ws(rest)?;
+        let (rest, functions) = many0(Function::parse_ws)(rest)?;
+        let (rest, _) = keywords::CurlyClose::parse_ws(rest)?;
+
+        let span = unsafe { from_to(input, rest) };
+
+        Ok((
+            rest,
+            Impl {
+                span,
+                attributes,
+                visibility,
+                interface,
+                ty,
+                constraints,
+                functions,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interfaces() {
+        let input = "interface Show t {
+            fun show(x: t) -> String
+            fun debug(x: t) -> String = \"<\" ++ show x ++ \">\"
+        }";
+
+        let (rest, interface) = Interface::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(interface.name, Identifier::from_str("Show"));
+        assert_eq!(interface.functions.len(), 2);
+        assert_eq!(interface.functions[0].default, None);
+        assert!(interface.functions[1].default.is_some());
+    }
+
+    #[test]
+    fn generic_interfaces() {
+        let input = [
+            "interface Ord t where t: Eq {
+                fun compare(a: t, b: t) -> Ordering
+            }",
+            "interface Convert (a, b) {
+                fun convert(x: a) -> b
+            }",
+            "interface Empty t {}",
+            "interface Functor f {
+                generic (a, b) fun fmap(x: f a, g: fun(a) => b) -> f b
+            }",
+        ];
+
+        for i in input {
+            let (rest, _interface) = Interface::parse(i).unwrap();
+            assert_eq!(rest, "", "expect to parse '{i}' without rest");
+        }
+    }
+
+    #[test]
+    fn impls() {
+        let input = "impl Show List t where t: Show {
+            fun show(xs: List t) -> String = join (xs /> map show) \", \"
+        }";
+
+        let (rest, implementation) = Impl::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(implementation.interface, IdentifierPath::from_str("Show"));
+        assert_eq!(implementation.ty, Type::from_str("List t"));
+        assert_eq!(implementation.constraints.len(), 1);
+        assert_eq!(implementation.functions.len(), 1);
+        assert_eq!(implementation.visibility, Visibility::Private);
+
+        let (rest, implementation) = Impl::parse("export impl Show Point {}").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(implementation.visibility, Visibility::Export);
+    }
+}
@@ -46,9 +46,13 @@ fn ident_char(input: &str) -> nom::IResult<&str, char> {
 
 keyword!(Abs, "|");
 keyword!(Add, "+", tag("+"));
+keyword!(AddAssign, "+=");
 keyword!(And, "and", ident_char);
 keyword!(Assign, "=");
+keyword!(As, "as", ident_char);
+keyword!(Async, "async", ident_char);
 keyword!(At, "@");
+keyword!(Await, "await", ident_char);
 keyword!(BracketClose, "]");
 keyword!(BracketOpen, "[");
 keyword!(Break, "break", ident_char);
@@ -56,9 +60,11 @@ keyword!(Colon, ":");
 keyword!(Comma, ",");
 keyword!(Comment, "#");
 keyword!(Concat, "++");
+keyword!(ConcatAssign, "++=");
 keyword!(CurlyClose, "}");
 keyword!(CurlyOpen, "{");
 keyword!(Divide, "/");
+keyword!(DivideAssign, "/=");
 keyword!(Do, "do", ident_char);
 keyword!(Dot, ".");
 keyword!(Else, "else", ident_char);
@@ -72,17 +78,23 @@ keyword!(Generic, "generic", ident_char);
 keyword!(Greater, ">");
 keyword!(GreaterEqual, ">=");
 keyword!(If, "if", ident_char);
+keyword!(Impl, "impl", ident_char);
 keyword!(In, "in", ident_char);
+keyword!(Interface, "interface", ident_char);
 keyword!(InlineExpressionStart, "$");
 keyword!(Is, "is", ident_char);
+keyword!(Label, "'");
 keyword!(Let, "let", ident_char);
 keyword!(Loop, "loop", ident_char);
 keyword!(Minus, "-");
 keyword!(Multiply, "*");
+keyword!(MultiplyAssign, "*=");
 keyword!(Star, "*");
 keyword!(Mut, "mut", ident_char);
 keyword!(Next, "next", ident_char);
 keyword!(Not, "!");
+keyword!(NotEqual, "!=");
+keyword!(Opaque, "opaque", ident_char);
 keyword!(Or, "or");
 keyword!(ParenClose, ")");
 keyword!(ParenOpen, "(");
@@ -90,6 +102,8 @@ keyword!(Pipe, "/>");
 keyword!(Plus, "+");
 keyword!(Power, "^");
 keyword!(Public, "pub", ident_char);
+keyword!(Question, "?");
+keyword!(RangeInclusive, "..=");
 keyword!(Return, "return", ident_char);
 keyword!(SemiColon, ";");
 keyword!(Smaller, "<");
@@ -99,7 +113,9 @@ keyword!(Sqrt, "√");
 keyword!(StringStart1, "\"");
 keyword!(StringStart2, "'");
 keyword!(Subtract, "-");
+keyword!(SubtractAssign, "-=");
 keyword!(Test, "test", ident_char);
+keyword!(Then, "then", ident_char);
 keyword!(BuildinType, "buildin_type", ident_char);
 keyword!(ThinArrow, "->");
 keyword!(True, "true", ident_char);
@@ -107,7 +123,9 @@ keyword!(Type, "type", ident_char);
 keyword!(TypeHint, ":");
 keyword!(Use, "use", ident_char);
 keyword!(When, "when", ident_char);
+keyword!(While, "while", ident_char);
 keyword!(Where, "where", ident_char);
+keyword!(With, "with", ident_char);
 keyword!(Identity, "&");
-keyword!(Yields, "yields");
-keyword!(Yield, "yield");
+keyword!(Yields, "yields", ident_char);
+keyword!(Yield, "yield", ident_char);
@@ -1,16 +1,22 @@
+mod attribute;
 pub mod body;
 pub mod expr;
 mod function;
 pub mod identifier;
 pub mod import;
+mod interface;
 pub mod keywords;
 mod structs;
 pub mod ty;
+mod visibility;
+pub use attribute::*;
 use body::BodyItem;
 pub use function::*;
 use import::Import;
+pub use interface::*;
 pub use structs::*;
 pub use ty::Type;
+pub use visibility::*;
 
 use crate::comment::parse_comment;
 
@@ -1,13 +1,14 @@
 use nom::{
     branch::alt,
-    combinator::{map, opt},
+    character::complete::space0,
+    combinator::{cut, map, opt},
     multi::{many1, separated_list1},
     sequence::{delimited, preceded},
 };
 
 use crate::{parse::Res, util::from_to, Parse};
 
-use super::{identifier::Identifier, keywords, Type};
+use super::{attributes, identifier::Identifier, keywords, Attribute, Type, Visibility};
 
 /// type Either (a, b)
 /// | Left: a
@@ -21,20 +22,43 @@ use super::{identifier::Identifier, keywords, Type};
 /// - name: String
 /// - age:  Int
 /// - likesBread: Boolean
+///
+/// type SortedSet t where t: Ord
+/// - items: List t
+///
+/// type UserId = Int
+///
+/// opaque type Email = String
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TypeDecl<'a> {
     pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
+    pub visibility: Visibility,
     pub name: Identifier<'a>,
     pub generic_symbols: Option<GenericSymbols<'a>>,
+    pub constraints: Vec<(Identifier<'a>, Vec<Type<'a>>)>,
     pub fields: EnumOrStructFields<'a>,
 }
 
 impl<'a> Parse<'a> for TypeDecl<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
-        let (rest, _) = keywords::Type::parse(input)?;
+        let (rest, attributes) = attributes(input)?;
+        let (rest, visibility) = Visibility::parse(rest)?;
+        let (rest, opaque) = opt(keywords::Opaque::parse)(rest)?;
+        let (rest, _) = keywords::Type::parse_ws(rest)?;
         let (rest, name) = Identifier::parse_ws(rest)?;
         let (rest, generic_args_decl) = opt(GenericSymbols::parse_ws)(rest)?;
-        let (rest, fields) = EnumOrStructFields::parse_ws(rest)?;
+        let (rest, constraints) = where_clause(rest)?;
+
+        let (rest, fields) = if opaque.is_some() {
+            // opaque types always wrap exactly one type
+            map(
+                preceded(keywords::Assign::parse_ws, cut(Type::parse_ws)),
+                EnumOrStructFields::Newtype,
+            )(rest)?
+        } else {
+            EnumOrStructFields::parse_ws(rest)?
+        };
 
         let span = unsafe { from_to(input, rest) };
 
@@ -42,8 +66,11 @@ impl<'a> Parse<'a> for TypeDecl<'a> {
             rest,
             TypeDecl {
                 span,
+                attributes,
+                visibility,
                 name,
                 generic_symbols: generic_args_decl,
+                constraints,
                 fields,
             },
         ))
@@ -80,10 +107,43 @@ impl<'a> Parse<'a> for GenericSymbols<'a> {
     }
 }
 
+/// Constraints on generic symbols
+/// e.g. where t: Ord, e: Show + Eq
+///
+/// Returns an empty list, if there is no where clause.
+pub(crate) fn where_clause(input: &str) -> Res<'_, Vec<(Identifier<'_>, Vec<Type<'_>>)>> {
+    use keywords::{Comma, Plus, TypeHint, Where};
+
+    let constraint = |input| {
+        // t
+        let (rest, symbol) = Identifier::parse_ws(input)?;
+        // :
+        let (rest, _) = TypeHint::parse_ws(rest)?;
+        // Ord + Show
+        // The + may not start a new line, as it would be a public struct field.
+        let (rest, types) = separated_list1(preceded(space0, Plus::parse), Type::parse_ws)(rest)?;
+
+        Ok((rest, (symbol, types)))
+    };
+
+    let (rest, constraints) = opt(preceded(
+        Where::parse_ws,
+        cut(separated_list1(Comma::parse_ws, constraint)),
+    ))(input)?;
+
+    Ok((rest, constraints.unwrap_or_default()))
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum EnumOrStructFields<'a> {
     EnumFields(Vec<EnumField<'a>>),
     StructFields(Vec<StructField<'a>>),
+    /// type UserId = Int
+    Alias(Type<'a>),
+    /// opaque type UserId = Int
+    ///
+    /// Distinct type, that is not interchangeable with the wrapped type.
+    Newtype(Type<'a>),
 }
 
 impl<'a> Parse<'a> for EnumOrStructFields<'a> {
@@ -94,6 +154,10 @@ impl<'a> Parse<'a> for EnumOrStructFields<'a> {
                 many1(StructField::parse_ws),
                 EnumOrStructFields::StructFields,
             ),
+            map(
+                preceded(keywords::Assign::parse, cut(Type::parse_ws)),
+                EnumOrStructFields::Alias,
+            ),
         ))(input)
     }
 }
@@ -104,26 +168,37 @@ impl<'a> Parse<'a> for EnumOrStructFields<'a> {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EnumField<'a> {
     pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
     pub name: Identifier<'a>,
     pub ty: Option<Type<'a>>,
 }
 
 impl<'a> Parse<'a> for EnumField<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
+        let (rest, attributes) = attributes(input)?;
         //      |
-        let (rest, _) = keywords::Abs::parse(input)?;
+        let (rest, _) = keywords::Abs::parse(rest)?;
         let (rest, name) = Identifier::parse_ws(rest)?;
         let (rest, ty) = opt(preceded(keywords::TypeHint::parse_ws, Type::parse_ws))(rest)?;
 
         let span = unsafe { from_to(input, rest) };
 
-        Ok((rest, EnumField { span, name, ty }))
+        Ok((
+            rest,
+            EnumField {
+                span,
+                attributes,
+                name,
+                ty,
+            },
+        ))
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct StructField<'a> {
     pub span: &'a str,
+    pub attributes: Vec<Attribute<'a>>,
     pub public: bool,
     pub mutable: bool,
     pub name: Identifier<'a>,
@@ -134,6 +209,8 @@ impl<'a> Parse<'a> for StructField<'a> {
     fn parse(input: &'a str) -> Res<'a, Self> {
         use keywords::{Minus, Mut, Plus, TypeHint};
 
+        let (rest, attributes) = attributes(input)?;
+
         // -
         // +
         let (rest, public) = alt((
@@ -141,7 +218,7 @@ impl<'a> Parse<'a> for StructField<'a> {
             map(Minus::parse, |_| false),
             // Otherwise it will be public
             map(Plus::parse, |_| true),
-        ))(input)?;
+        ))(rest)?;
 
         // mut
         let (rest, mutable) = opt(Mut::parse_ws)(rest)?;
@@ -160,6 +237,7 @@ impl<'a> Parse<'a> for StructField<'a> {
             rest,
             StructField {
                 span,
+                attributes,
                 public,
                 mutable,
                 name,
@@ -192,6 +270,17 @@ mod tests {
             "type Person 
             - birthday : Date
             - name : String",
+            "type SortedSet t where t: Ord
+            - items: List t",
+            "type Dict (k, v) where k: Hash + Eq, v: Show
+            - entries: List (k, v)",
+            "type UserId = Int",
+            "type Pair t = (t, t)",
+            "type Parser t = fun(String) => Result t",
+            "opaque type Email = String",
+            "opaque type Sorted t where t: Ord = List t",
+            "export type Meters = Float",
+            "pub opaque type Token = String",
         ];
 
         for i in &input {
@@ -201,4 +290,103 @@ mod tests {
             assert_eq!(&value.span, i);
         }
     }
+
+    #[test]
+    fn type_aliases() {
+        let (rest, value) = TypeDecl::parse("type Matrix t = List (List t)").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            value.fields,
+            EnumOrStructFields::Alias(Type::from_str("List (List t)"))
+        );
+
+        let (rest, value) = TypeDecl::parse("type Pair t = (t, t)").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(value.generic_symbols, Some(GenericSymbols::from_str("t")));
+        let EnumOrStructFields::Alias(Type::Tuple { values, .. }) = value.fields else {
+            panic!("expected a tuple alias, got {:?}", value.fields);
+        };
+        assert_eq!(values, vec![Type::from_str("t"), Type::from_str("t")]);
+
+        let (rest, value) = TypeDecl::parse("opaque type UserId = Int").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            value.fields,
+            EnumOrStructFields::Newtype(Type::from_str("Int"))
+        );
+
+        // opaque types may only wrap a single type
+        assert!(TypeDecl::parse("opaque type Bit | Zero | One").is_err());
+    }
+
+    #[test]
+    fn field_attributes() {
+        let input = "type Color
+            | Red
+            @deprecated 'use Red'
+            | Crimson";
+        let (rest, value) = TypeDecl::parse(input).unwrap();
+        assert_eq!(rest, "");
+
+        let EnumOrStructFields::EnumFields(fields) = value.fields else {
+            panic!("expected enum fields");
+        };
+        assert!(fields[0].attributes.is_empty());
+        assert_eq!(fields[1].attributes[0].span, "@deprecated 'use Red'");
+        assert_eq!(
+            fields[1].span,
+            "@deprecated 'use Red'\n            | Crimson"
+        );
+
+        let input = "@derive(Eq) type Point
+            @since 2
+            - x: Int";
+        let (rest, value) = TypeDecl::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(value.attributes.len(), 1);
+
+        let EnumOrStructFields::StructFields(fields) = value.fields else {
+            panic!("expected struct fields");
+        };
+        assert_eq!(fields[0].attributes[0].span, "@since 2");
+    }
+
+    #[test]
+    fn type_constraints() {
+        let input = "type Dict (k, v) where k: Hash + Eq, v: Show | Empty";
+        let (rest, value) = TypeDecl::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            value.constraints,
+            vec![
+                (
+                    Identifier::from_str("k"),
+                    vec![Type::from_str("Hash"), Type::from_str("Eq")]
+                ),
+                (Identifier::from_str("v"), vec![Type::from_str("Show")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn struct_constraints() {
+        let input = "type SortedSet t where t: Ord + Show
++ items: List t
+- size: Int";
+        let (rest, value) = TypeDecl::parse(input).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            value.constraints,
+            vec![(
+                Identifier::from_str("t"),
+                vec![Type::from_str("Ord"), Type::from_str("Show")]
+            )]
+        );
+        let EnumOrStructFields::StructFields(fields) = value.fields else {
+            panic!("expected struct fields, got {:?}", value.fields);
+        };
+        assert_eq!(fields.len(), 2);
+        assert!(fields[0].public);
+        assert_eq!(fields[0].name, Identifier::from_str("items"));
+    }
 }
@@ -1,6 +1,7 @@
 use nom::{
     branch::alt,
     combinator::{map, opt},
+    multi::many0,
     sequence::preceded,
 };
 
@@ -9,9 +10,17 @@ use crate::{
     Parse,
 };
 
-use super::identifier::Identifier;
-use crate::ast::keywords::{Comma, FatArrow, Function, ParenClose, ParenOpen};
+use super::identifier::{Identifier, IdentifierPath};
+use crate::ast::keywords::{
+    BracketClose, BracketOpen, Comma, FatArrow, Function, ParenClose, ParenOpen, Question,
+};
 
+/// List Int
+/// Map String (List Int)
+/// fun(Int, Int) => Int
+/// (Int, String)
+/// [Int]
+/// Int?
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Type<'a> {
     Normal {
@@ -19,20 +28,85 @@ pub enum Type<'a> {
         name: Identifier<'a>,
         generic: Vec<Type<'a>>,
     },
+    /// std.collections.HashMap k v
+    Qualified {
+        span: &'a str,
+        path: IdentifierPath<'a>,
+        generic: Vec<Type<'a>>,
+    },
     Function {
         span: &'a str,
         args: Vec<Type<'a>>,
         ret: Option<Box<Type<'a>>>,
     },
+    /// ()
+    Unit { span: &'a str },
+    /// (Int, String)
+    Tuple {
+        span: &'a str,
+        values: Vec<Type<'a>>,
+    },
+    /// [Int]
+    Array {
+        span: &'a str,
+        element: Box<Type<'a>>,
+    },
+    /// Int?
+    Optional { span: &'a str, ty: Box<Type<'a>> },
+}
+
+impl<'a> Type<'a> {
+    pub fn span(&self) -> &'a str {
+        match self {
+            Type::Normal { span, .. }
+            | Type::Qualified { span, .. }
+            | Type::Function { span, .. }
+            | Type::Unit { span }
+            | Type::Tuple { span, .. }
+            | Type::Array { span, .. }
+            | Type::Optional { span, .. } => span,
+        }
+    }
 }
 
 impl<'a> Parse<'a> for Type<'a> {
     fn parse(input: &'a str) -> crate::parse::Res<'a, Self> {
-        alt((parse_function, parse_normal))(input)
+        let (rest, ty) = alt((parse_function, parse_paren, parse_array, parse_normal))(input)?;
+
+        parse_optional(input, rest, ty)
+    }
+}
+
+/// Wraps the type into `Type::Optional`, if it is followed by a `?`
+fn parse_optional<'a>(
+    input: &'a str,
+    rest: &'a str,
+    ty: Type<'a>,
+) -> crate::parse::Res<'a, Type<'a>> {
+    if let Ok((rest, _)) = Question::parse(rest) {
+        let span = unsafe { from_to(input, rest) };
+        let ty = Box::new(ty);
+
+        return Ok((rest, Type::Optional { span, ty }));
     }
+
+    Ok((rest, ty))
 }
 
-fn parse_function(input: &str) -> crate::parse::Res<'_, Type> {
+/// Types, that may be used as generic arguments without parenthesis.
+/// e.g. `Int`, `std.String`, `(Map k v)`, `[Int]` or `Int?`
+pub(crate) fn parse_generic_arg(input: &str) -> crate::parse::Res<'_, Type<'_>> {
+    let path = |input| {
+        let (rest, path) = IdentifierPath::parse(input)?;
+        Ok((rest, from_path(input, rest, path, Vec::new())))
+    };
+
+    let (rest, ty) = alt((parse_paren, parse_array, path))(input)?;
+
+    parse_optional(input, rest, ty)
+}
+
+fn parse_function(input: &str) -> crate::parse::Res<'_, Type<'_>> {
     let (rest, _) = Function::parse(input)?;
     let (rest, _) = ParenOpen::parse_ws(rest)?;
 
@@ -48,30 +122,76 @@ fn parse_function(input: &str) -> crate::parse::Res<'_, Type> {
     Ok((rest, Type::Function { span, args, ret }))
 }
 
-fn parse_normal(input: &str) -> crate::parse::Res<'_, Type> {
-    let generic_1 = map(Type::parse_ws, |t| vec![t]);
-    let generic_many = |input| {
-        let (rest, _) = ParenOpen::parse_ws(input)?;
-        let (rest, items) = joined_by0(Type::parse_ws, Comma::parse_ws)(rest)?;
-        let (rest, _) = ParenClose::parse_ws(rest)?;
+/// ()
+/// (Int, String)
+/// (Map k v)
+///
+/// As a generic argument, a parenthesized list is a single tuple type.
+/// `Map (String, Json)` used to take two generic arguments and now takes one,
+/// write `Map String Json` instead.
+fn parse_paren(input: &str) -> crate::parse::Res<'_, Type<'_>> {
+    let (rest, _) = ParenOpen::parse(input)?;
+    let (rest, mut values) = joined_by0(Type::parse_ws, Comma::parse_ws)(rest)?;
+    let (rest, _) = ParenClose::parse_ws(rest)?;
 
-        Ok((rest, items))
+    let span = unsafe { from_to(input, rest) };
+
+    let ty = match values.len() {
+        0 => Type::Unit { span },
+        // Parenthesis only group types
+        1 => values.remove(0),
+        _ => Type::Tuple { span, values },
     };
 
-    let (rest, name) = Identifier::parse(input)?;
-    let (rest, generic) = opt(alt((generic_1, generic_many)))(rest)?;
-    let generic = generic.unwrap_or_default();
+    Ok((rest, ty))
+}
+
+/// [Int]
+fn parse_array(input: &str) -> crate::parse::Res<'_, Type<'_>> {
+    let (rest, _) = BracketOpen::parse(input)?;
+    let (rest, element) = map(Type::parse_ws, Box::new)(rest)?;
+    let (rest, _) = BracketClose::parse_ws(rest)?;
+
+    let span = unsafe { from_to(input, rest) };
+
+    Ok((rest, Type::Array { span, element }))
+}
+
+/// List Int
+/// Map k (List v)
+/// std.collections.HashMap k v
+fn parse_normal(input: &str) -> crate::parse::Res<'_, Type<'_>> {
+    use crate::comment::parse_comment;
+
+    let (rest, path) = IdentifierPath::parse(input)?;
+    let (rest, generic) = many0(preceded(parse_comment, parse_generic_arg))(rest)?;
 
+    Ok((rest, from_path(input, rest, path, generic)))
+}
+
+fn from_path<'a>(
+    input: &'a str,
+    rest: &'a str,
+    mut path: IdentifierPath<'a>,
+    generic: Vec<Type<'a>>,
+) -> Type<'a> {
     let span = unsafe { from_to(input, rest) };
 
-    Ok((
-        rest,
-        Type::Normal {
+    if path.value.len() == 1 {
+        let name = path.value.remove(0);
+
+        return Type::Normal {
             span,
             name,
             generic,
-        },
-    ))
+        };
+    }
+
+    Type::Qualified {
+        span,
+        path,
+        generic,
+    }
 }
 
 #[cfg(test)]
@@ -134,4 +254,132 @@ mod tests {
             }
         )
     }
+
+    fn parse(input: &str) -> Type<'_> {
+        let (rest, ty) = Type::parse(input).unwrap();
+        assert_eq!(rest, "", "expect to parse '{input}' without rest");
+        ty
+    }
+
+    #[test]
+    fn multiple_generic_args() {
+        let Type::Normal { generic, .. } = parse("Map String (List Int)") else {
+            panic!("expected normal type");
+        };
+
+        assert_eq!(generic.len(), 2);
+        assert_eq!(generic[0], Type::from_str("String"));
+        assert_eq!(generic[1], Type::from_str("List Int"));
+    }
+
+    #[test]
+    fn qualified_type() {
+        let input = "std.collections.HashMap k v";
+        let Type::Qualified {
+            span,
+            path,
+            generic,
+        } = parse(input)
+        else {
+            panic!("expected qualified type");
+        };
+
+        assert_eq!(span, input);
+        assert_eq!(path, IdentifierPath::from_str("std.collections.HashMap"));
+        assert_eq!(generic.len(), 2);
+    }
+
+    #[test]
+    fn tuples_and_unit() {
+        assert_eq!(parse("()"), Type::Unit { span: "()" });
+        assert_eq!(
+            parse("(Int, String)"),
+            Type::Tuple {
+                span: "(Int, String)",
+                values: vec![Type::from_str("Int"), Type::from_str("String")],
+            }
+        );
+        // parenthesis only group
+        assert_eq!(parse("(List Int)"), Type::from_str("List Int"));
+    }
+
+    #[test]
+    fn arrays_and_optionals() {
+        assert_eq!(
+            parse("[Int]"),
+            Type::Array {
+                span: "[Int]",
+                element: Box::new(Type::from_str("Int")),
+            }
+        );
+        assert_eq!(
+            parse("Int?"),
+            Type::Optional {
+                span: "Int?",
+                ty: Box::new(Type::from_str("Int")),
+            }
+        );
+
+        // ? binds to the generic argument
+        let Type::Normal { generic, .. } = parse("List Int?") else {
+            panic!("expected normal type");
+        };
+        assert!(matches!(generic[0], Type::Optional { .. }));
+
+        assert!(matches!(parse("(List Int)?"), Type::Optional { .. }));
+    }
+
+    #[test]
+    fn complex_types() {
+        let input = [
+            "std.List",
+            "std.List String",
+            "Map (String, Json)",
+            "List (Map String Json)",
+            "[[Float]]",
+            "List (List String)",
+            "fun(Int) => Int?",
+            "fun(fun(Int) => Int, [Int]) => [Int]",
+            "Result () String",
+            "List (fun() => ())",
+        ];
+
+        for i in input {
+            parse(i);
+        }
+
+        // a single tuple argument, not the two arguments of `Map String Json`
+        assert_eq!(
+            parse("Map (String, Json)"),
+            Type::Normal {
+                span: "Map (String, Json)",
+                name: Identifier {
+                    span: "Map",
+                    value: "Map",
+                },
+                generic: vec![Type::Tuple {
+                    span: "(String, Json)",
+                    values: vec![Type::from_str("String"), Type::from_str("Json")],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn generic_args_with_whitespace() {
+        for input in [
+            "Map\tString\tInt",
+            "Map # keys\n    String # values\n    Int",
+        ] {
+            let (rest, ty) = Type::parse(input).unwrap();
+            assert_eq!(rest, "");
+            let Type::Normal { generic, .. } = ty else {
+                panic!("expected a normal type, got {:?}", ty);
+            };
+            assert_eq!(
+                generic,
+                vec![Type::from_str("String"), Type::from_str("Int")]
+            );
+        }
+    }
 }
@@ -0,0 +1,85 @@
+use nom::{branch::alt, combinator::map};
+
+use crate::{ast::keywords, parse::*};
+
+/// Who may access an item.
+/// e.g.
+/// fun helper() = ...
+/// pub fun internal() = ...
+/// export fun api() = ...
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Visibility {
+    /// Only visible inside of its file
+    #[default]
+    Private,
+    /// `pub`: visible inside of its package
+    Package,
+    /// `export`: visible to everyone, including other packages
+    Export,
+}
+
+impl Visibility {
+    pub fn is_exported(self) -> bool {
+        self == Visibility::Export
+    }
+}
+
+impl<'a> Parse<'a> for Visibility {
+    /// Never fails, as a missing modifier means `Private`.
+    /// When a modifier was found, the following whitespace is consumed as well.
+    fn parse(input: &'a str) -> Res<'a, Self> {
+        let modifier = alt((
+            map(keywords::Export::parse, |_| Visibility::Export),
+            map(keywords::Public::parse, |_| Visibility::Package),
+        ))(input);
+
+        match modifier {
+            Ok((rest, visibility)) => {
+                let (rest, _) = crate::comment::parse_comment(rest)?;
+                Ok((rest, visibility))
+            }
+            Err(_) => Ok((input, Visibility::Private)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visibility_modifiers() {
+        assert_eq!(
+            Visibility::parse("export fun"),
+            Ok(("fun", Visibility::Export))
+        );
+        assert_eq!(
+            Visibility::parse("pub type"),
+            Ok(("type", Visibility::Package))
+        );
+        assert_eq!(Visibility::parse("fun"), Ok(("fun", Visibility::Private)));
+        // keywords need a word boundary
+        assert_eq!(
+            Visibility::parse("publish"),
+            Ok(("publish", Visibility::Private))
+        );
+    }
+
+    #[test]
+    fn exported_item_after_arguments() {
+        use crate::ast::body::BodyItem;
+
+        // `export` is neither a type argument nor a function argument
+        for input in [
+            "type A = List\nexport fun f() = 1",
+            "fun g() = print 1\nexport fun f() = 1",
+        ] {
+            let ast = crate::Ast::from_source_code(input).unwrap();
+            assert_eq!(ast.items.len(), 2);
+            let BodyItem::Function(f) = &ast.items[1] else {
+                panic!("expected a function, got {:?}", ast.items[1]);
+            };
+            assert_eq!(f.visibility, Visibility::Export);
+        }
+    }
+}
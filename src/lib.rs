@@ -17,7 +17,7 @@ mod syntax_tests {
 
         let result = Ast::from_source_code(input);
 
-        let ast = result.expect("To parse Ast");
+        let _ast = result.expect("To parse Ast");
     }
 
     #[test]
@@ -20,6 +20,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 pub trait Combinator<O> {
     fn ws(self) -> O;
 }
@@ -1,5 +1,10 @@
 use crate::parse::Res;
 
+/// Returns the slice of source code between `start` and `end`.
+///
+/// # Safety
+/// `end` must be a suffix of `start`, i.e. both slices must point into the same string
+/// and `end` may not begin before `start`.
 pub unsafe fn from_to<'a>(start: &'a str, end: &'a str) -> &'a str {
     // TODO implement safety measures. Panic
     let length = end.as_ptr() as usize - start.as_ptr() as usize;
@@ -9,6 +14,15 @@ pub unsafe fn from_to<'a>(start: &'a str, end: &'a str) -> &'a str {
     std::str::from_utf8_unchecked(bytes)
 }
 
+/// Turns a recoverable error into a failure, just like `nom::combinator::cut`.
+/// Useful, once a parser committed to one of several alternatives.
+pub fn commit<T>(result: Res<'_, T>) -> Res<'_, T> {
+    result.map_err(|e| match e {
+        nom::Err::Error(e) => nom::Err::Failure(e),
+        e => e,
+    })
+}
+
 /// Allows Items to be separated by a parser, while also allowing for that parser to be trailing.
 /// Accepts, if item is matched at least one time
 pub fn joined_by1<'a, I, T>(
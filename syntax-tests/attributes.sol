@@ -0,0 +1,20 @@
+@deprecated "use Vector"
+type Point
+- x: Int
+@since 2
+- y: Int
+
+type Shape
+| Circle: Float
+@deprecated
+| Square: Float
+
+@inline
+# doc comments may go in between
+@cfg(debug)
+export fun area(s: Shape) = 0
+
+@test.ignore
+test "area" {
+    area (Circle 1)
+}
@@ -0,0 +1,17 @@
+
+interface Show t {
+    fun show(x: t) -> String
+    fun debug(x: t) -> String = "<" ++ show x ++ ">"
+}
+
+type Point
+- x: Int
+- y: Int
+
+impl Show Point {
+    fun show(p: Point) -> String = "Point"
+}
+
+impl Show List t where t: Show {
+    fun show(xs: List t) -> String = join (xs /> map show) ", "
+}
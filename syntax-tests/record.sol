@@ -0,0 +1,9 @@
+type Point
+- x: Int
+- y: Int
+
+fun origin() = Point { x = 0, y = 0 }
+
+fun unit_x() = Point x=1 y=0
+
+fun move_right(p: Point) = { p with x = p.x + 1 }
@@ -0,0 +1,16 @@
+export use std.io..
+pub use std.(fs)
+
+export type Meters = Float
+pub opaque type Token = String
+export buildin_type Bytes
+
+export let origin = 0
+pub let limit = 100
+
+export interface Show t {
+    fun show(x: t) -> String
+}
+
+pub fun helper() = limit
+export fun main() = helper ()